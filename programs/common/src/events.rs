@@ -0,0 +1,397 @@
+use anchor_lang::prelude::*;
+use crate::shared_types::*;
+
+// Events shared by the Botanika programs, emitted through `emit_cpi!` so indexers
+// can read them from instruction data instead of truncatable logs
+
+/// Tokens deposited into a stake position by `open_position` or `stake`
+#[event]
+pub struct Staked {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position_id: u64,
+    pub amount: u64,
+    pub fee: u64,
+    pub receipts_minted: u64,
+    pub new_stake_amount: u64,
+    pub level_before: u8,
+    pub level_after: u8,
+    pub lockup_end: i64,
+    pub timestamp: i64,
+}
+
+/// Tokens taken out of a stake position, into an unbonding ticket (`unstake`) or straight to the
+/// owner at a penalty (`unstake_early`)
+#[event]
+pub struct Unstaked {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position_id: u64,
+    pub ticket_id: Option<u64>,
+    pub amount: u64,
+    pub fee: u64,
+    pub penalty: u64,
+    pub receipts_burned: u64,
+    pub remaining_amount: u64,
+    pub level_before: u8,
+    pub level_after: u8,
+    pub timestamp: i64,
+}
+
+/// Staking rewards paid out by `claim`
+#[event]
+pub struct RewardsClaimed {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position_id: u64,
+    pub amount: u64,
+    pub fee: u64,
+    pub receipts_burned: u64,
+    pub level_before: u8,
+    pub level_after: u8,
+    pub timestamp: i64,
+}
+
+/// Rewards restaked into their position by `compound`
+#[event]
+pub struct RewardsCompounded {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position_id: u64,
+    pub amount: u64,
+    pub fee: u64,
+    pub receipts_burned: u64,
+    pub new_stake_amount: u64,
+    pub level: u8,
+    pub timestamp: i64,
+}
+
+/// A position's level and loyalty boost re-applied by the `refresh_position` crank
+#[event]
+pub struct PositionRefreshed {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position_id: u64,
+    pub level_before: u8,
+    pub level_after: u8,
+    pub weighted_amount: u64,
+    pub cranker: Pubkey,
+    pub timestamp: i64,
+}
+
+/// An unbonding ticket paid out by `withdraw`
+#[event]
+pub struct Withdrawn {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position_id: u64,
+    pub ticket_id: u64,
+    pub amount: u64,
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
+/// An unbonding ticket restaked into its position by `cancel_unbonding`
+#[event]
+pub struct UnbondingCancelled {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position_id: u64,
+    pub ticket_id: u64,
+    pub amount: u64,
+    pub receipts_minted: u64,
+    pub new_stake_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Receipts left on a position without stake burned by `burn_receipts`
+#[event]
+pub struct ReceiptsBurned {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position_id: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// An empty position closed by its owner, or swept by the admin
+#[event]
+pub struct StakeClosed {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position_id: u64,
+    pub rent_receiver: Pubkey,
+    pub swept: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionTransferred {
+    pub pool: Pubkey,
+    pub from: Pubkey,
+    pub from_position_id: u64,
+    pub to: Pubkey,
+    pub to_position_id: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmergencyWithdrawn {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position_id: u64,
+    pub amount: u64,
+    pub receipts_burned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeSlashed {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position_id: u64,
+    pub ticket_id: Option<u64>, // set when an unbonding ticket was slashed rather than the stake
+    pub amount: u64,
+    pub slash_bps: u64,
+    pub reason_code: u16,
+    pub frozen: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeUnfrozen {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position_id: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// A staking pool created by `initialize`
+#[event]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub pool_id: u64,
+    pub admin: Pubkey,
+    pub staking_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub receipt_mint: Pubkey,
+    pub reward_rate_per_slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsFunded {
+    pub pool: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub total_funded: u64,
+    pub total_owed: u64,
+    pub runway_slots: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardStreamAdded {
+    pub pool: Pubkey,
+    pub index: u8,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub rate_per_slot: u64,
+    pub start_slot: u64,
+    pub end_slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmissionScheduleUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub curve: EmissionCurve,
+    pub segments: Vec<RateSegment>,
+    pub decay: DecayCurve,
+    pub emission_cap: u64,
+    pub total_emitted: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub reward_rate_per_slot: u64,
+    pub level_window: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GovernanceSynced {
+    pub pool: Pubkey,
+    pub level_tiers: Vec<LevelTier>,
+    pub lockup_period: i64,
+    pub claim_cooldown: i64,
+    pub loyalty_bps_per_period: u64,
+    pub loyalty_period: i64,
+    pub max_loyalty_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolTermsUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    /// `None` for each setting handed back to governance
+    pub level_tiers: Option<Vec<LevelTier>>,
+    pub lock_tiers: Option<Vec<LockTier>>,
+    pub lockup_period: Option<i64>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminProposed {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub pool: Pubkey,
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TransfersRestrictedUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub restricted: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SlashingAuthorityUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// The rewards program's pool created by `initialize_reward_pool`
+#[event]
+pub struct RewardPoolInitialized {
+    pub authority: Pubkey,
+    pub reward_mint: Pubkey,
+    pub reward_vault: Pubkey,
+    pub expiration_period: i64,
+    pub timestamp: i64,
+}
+
+/// A proof-based reward recorded by the rewards authority
+#[event]
+pub struct RewardSubmitted {
+    pub authority: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub proof_type: ProofType,
+    pub expires_at: i64,
+    pub total_rewards_distributed: u64,
+    pub timestamp: i64,
+}
+
+/// A proof-based reward paid to its recipient
+#[event]
+pub struct RewardClaimed {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub proof_type: ProofType,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ChangeProposed {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub proposal_type: ProposalType,
+    pub voting_ends_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultiplierSet {
+    pub authority: Pubkey,
+    pub level: u8,
+    pub old_multiplier: u64,
+    pub new_multiplier: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub proposal_id: u64,
+    pub proposal_type: ProposalType,
+    pub authority: Pubkey,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GovernanceInitialized {
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LevelTiersSet {
+    pub authority: Pubkey,
+    pub level_tiers: Vec<LevelTier>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LockTierSet {
+    pub authority: Pubkey,
+    pub index: u8,
+    pub duration: i64,
+    pub multiplier: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakingPeriodsSet {
+    pub authority: Pubkey,
+    pub lockup_period: i64,
+    pub claim_cooldown: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LoyaltyBoostSet {
+    pub authority: Pubkey,
+    pub bps_per_period: u64,
+    pub period: i64,
+    pub max_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EarlyUnstakePenaltySet {
+    pub authority: Pubkey,
+    pub penalty_bps: u64,
+    pub destination: PenaltyDestination,
+    pub timestamp: i64,
+}
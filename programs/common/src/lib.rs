@@ -1,7 +1,9 @@
 pub mod shared_types;
 pub mod validation;
 pub mod math;
+pub mod events;
 
 pub use shared_types::*;
-pub use validation::*;
-pub use math::*; 
\ No newline at end of file
+pub use validation::validation::*;
+pub use math::math::*;
+pub use events::*; 
\ No newline at end of file
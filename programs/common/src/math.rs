@@ -7,23 +7,23 @@ pub mod math {
 
     /// Safe addition with overflow check
     pub fn safe_add(a: u64, b: u64) -> Result<u64> {
-        a.checked_add(b).ok_or(BotanikaError::Overflow)
+        a.checked_add(b).ok_or_else(|| error!(BotanikaError::Overflow))
     }
 
     /// Safe subtraction with underflow check
     pub fn safe_sub(a: u64, b: u64) -> Result<u64> {
-        a.checked_sub(b).ok_or(BotanikaError::Overflow)
+        a.checked_sub(b).ok_or_else(|| error!(BotanikaError::Overflow))
     }
 
     /// Safe multiplication with overflow check
     pub fn safe_mul(a: u64, b: u64) -> Result<u64> {
-        a.checked_mul(b).ok_or(BotanikaError::Overflow)
+        a.checked_mul(b).ok_or_else(|| error!(BotanikaError::Overflow))
     }
 
     /// Safe division with zero check
     pub fn safe_div(a: u64, b: u64) -> Result<u64> {
         require!(b > 0, BotanikaError::InvalidAmount);
-        a.checked_div(b).ok_or(BotanikaError::Overflow)
+        a.checked_div(b).ok_or_else(|| error!(BotanikaError::Overflow))
     }
 
     /// Calculate reward based on amount, time, and multiplier
@@ -45,13 +45,407 @@ pub mod math {
         safe_div(fee, 10000)
     }
 
-    /// Calculate staking level from amount
-    pub fn calculate_staking_level(amount: u64) -> StakingLevel {
-        match amount {
-            0..=1000 => StakingLevel::Bronze,
-            1001..=5000 => StakingLevel::Silver,
-            5001..=10000 => StakingLevel::Gold,
-            _ => StakingLevel::Platinum,
+    /// Calculate the early unstake penalty, decaying linearly from `max_penalty_bps`
+    /// at the start of the lockup to zero at its end
+    pub fn calculate_early_unstake_penalty(
+        amount: u64,
+        max_penalty_bps: u64,
+        remaining_lockup: i64,
+        lock_duration: i64,
+    ) -> Result<u64> {
+        if remaining_lockup <= 0 || lock_duration <= 0 {
+            return Ok(0);
+        }
+        let remaining_lockup = remaining_lockup.min(lock_duration) as u128;
+        let penalty = (amount as u128)
+            .checked_mul(max_penalty_bps as u128)
+            .ok_or(BotanikaError::Overflow)?
+            .checked_mul(remaining_lockup)
+            .ok_or(BotanikaError::Overflow)?
+            .checked_div(10000 * lock_duration as u128)
+            .ok_or(BotanikaError::Overflow)?;
+        u64::try_from(penalty).map_err(|_| error!(BotanikaError::Overflow))
+    }
+
+    /// Calculate the reward weight of a stake (amount scaled by its multiplier)
+    pub fn calculate_weighted_amount(amount: u64, multiplier: u64) -> Result<u64> {
+        let weighted = (amount as u128)
+            .checked_mul(multiplier as u128)
+            .ok_or(BotanikaError::Overflow)?
+            .checked_div(BASE_MULTIPLIER as u128)
+            .ok_or(BotanikaError::Overflow)?;
+        u64::try_from(weighted).map_err(|_| error!(BotanikaError::Overflow))
+    }
+
+    /// Calculate the reward debt of a weighted stake at the given accumulator value
+    pub fn calculate_reward_debt(weighted_amount: u64, acc_reward_per_share: u128) -> Result<u64> {
+        let debt = (weighted_amount as u128)
+            .checked_mul(acc_reward_per_share)
+            .ok_or(BotanikaError::Overflow)?
+            .checked_div(ACC_REWARD_PRECISION)
+            .ok_or(BotanikaError::Overflow)?;
+        u64::try_from(debt).map_err(|_| error!(BotanikaError::Overflow))
+    }
+
+    /// Calculate rewards accrued by a weighted stake since its debt was last settled
+    pub fn calculate_pending_reward(
+        weighted_amount: u64,
+        acc_reward_per_share: u128,
+        reward_debt: u64,
+    ) -> Result<u64> {
+        let accrued = calculate_reward_debt(weighted_amount, acc_reward_per_share)?;
+        safe_sub(accrued, reward_debt)
+    }
+
+    /// Calculate the receipt exchange rate, in stake tokens per receipt token scaled by
+    /// `EXCHANGE_RATE_PRECISION`. Starts at 1.0 while no receipts exist.
+    pub fn calculate_exchange_rate(pooled_value: u64, receipt_supply: u64) -> Result<u128> {
+        if receipt_supply == 0 || pooled_value == 0 {
+            return Ok(EXCHANGE_RATE_PRECISION);
+        }
+        let rate = (pooled_value as u128)
+            .checked_mul(EXCHANGE_RATE_PRECISION)
+            .ok_or(BotanikaError::Overflow)?
+            .checked_div(receipt_supply as u128)
+            .ok_or(BotanikaError::Overflow)?;
+        Ok(rate)
+    }
+
+    /// Calculate the receipt tokens minted for depositing `amount` into a pool worth
+    /// `pooled_value`, rounded down in favour of existing holders
+    pub fn calculate_receipt_amount(amount: u64, pooled_value: u64, receipt_supply: u64) -> Result<u64> {
+        if receipt_supply == 0 || pooled_value == 0 {
+            return Ok(amount);
+        }
+        let receipts = (amount as u128)
+            .checked_mul(receipt_supply as u128)
+            .ok_or(BotanikaError::Overflow)?
+            .checked_div(pooled_value as u128)
+            .ok_or(BotanikaError::Overflow)?;
+        u64::try_from(receipts).map_err(|_| error!(BotanikaError::Overflow))
+    }
+
+    /// Calculate the receipt tokens worth `value` in a pool worth `pooled_value`, burned when that
+    /// value leaves the pool. Rounded up, so redeeming never lowers the rate for the holders left.
+    pub fn calculate_receipt_redemption(value: u64, pooled_value: u64, receipt_supply: u64) -> Result<u64> {
+        if pooled_value == 0 {
+            return Ok(receipt_supply);
+        }
+        let numerator = (value as u128)
+            .checked_mul(receipt_supply as u128)
+            .ok_or(BotanikaError::Overflow)?;
+        let receipts = numerator
+            .checked_add(pooled_value as u128 - 1)
+            .ok_or(BotanikaError::Overflow)?
+            .checked_div(pooled_value as u128)
+            .ok_or(BotanikaError::Overflow)?;
+        u64::try_from(receipts).map_err(|_| error!(BotanikaError::Overflow))
+    }
+
+    /// Fixed-point scale of decay factors
+    const DECAY_PRECISION: u128 = 1_000_000_000_000;
+
+    /// `(1 - decay_bps / 10000)^periods` scaled by `DECAY_PRECISION`, by square-and-multiply
+    fn decay_factor(decay_bps: u64, periods: u64) -> u128 {
+        let mut factor = DECAY_PRECISION;
+        let mut base = (10000 - decay_bps) as u128 * DECAY_PRECISION / 10000;
+        let mut remaining = periods;
+        while remaining > 0 && factor > 0 {
+            if remaining & 1 == 1 {
+                factor = factor * base / DECAY_PRECISION;
+            }
+            base = base * base / DECAY_PRECISION;
+            remaining >>= 1;
+        }
+        factor
+    }
+
+    /// Calculate an emission rate after `periods` decay steps of `decay_bps` each, never below
+    /// `floor_rate`. A `decay_bps` of 5000 halves the rate every period.
+    pub fn calculate_decayed_rate(initial_rate: u64, decay_bps: u64, periods: u64, floor_rate: u64) -> Result<u64> {
+        require!(decay_bps <= 10000, BotanikaError::InvalidRewardRate);
+        let rate = (initial_rate as u128)
+            .checked_mul(decay_factor(decay_bps, periods))
+            .ok_or(BotanikaError::Overflow)?
+            / DECAY_PRECISION;
+        Ok((rate as u64).max(floor_rate))
+    }
+
+    /// Sum of the per-slot rates of `periods` whole decay periods starting at `first_period`, as
+    /// `calculate_decayed_rate` would give them one by one (up to rounding). Multiply by the period
+    /// length for tokens. Runs in O(log) steps however many periods are covered: the periods above
+    /// `floor_rate` form a geometric series, the rest all pay the floor.
+    pub fn calculate_decayed_emission(
+        initial_rate: u64,
+        decay_bps: u64,
+        first_period: u64,
+        periods: u64,
+        floor_rate: u64,
+    ) -> Result<u128> {
+        require!(decay_bps > 0 && decay_bps <= 10000, BotanikaError::InvalidRewardRate);
+        if periods == 0 {
+            return Ok(0);
+        }
+
+        // Rate of the first period before the floor, scaled by DECAY_PRECISION
+        let first_rate = (initial_rate as u128)
+            .checked_mul(decay_factor(decay_bps, first_period))
+            .ok_or(BotanikaError::Overflow)?;
+        // Smallest scaled rate that still rounds to above the floor
+        let above_floor_min = (floor_rate as u128 + 1)
+            .checked_mul(DECAY_PRECISION)
+            .ok_or(BotanikaError::Overflow)?;
+
+        // Count the leading periods above the floor, trying the largest powers of two first
+        let mut above_floor: u64 = 0;
+        if first_rate >= above_floor_min {
+            let mut powers = [0u128; 64];
+            powers[0] = (10000 - decay_bps) as u128 * DECAY_PRECISION / 10000;
+            for bit in 1..64 {
+                powers[bit] = powers[bit - 1] * powers[bit - 1] / DECAY_PRECISION;
+            }
+            above_floor = 1;
+            let mut last_rate = first_rate;
+            for bit in (0..64).rev() {
+                let step = 1u64 << bit;
+                if step > periods - above_floor {
+                    continue;
+                }
+                let rate = last_rate * powers[bit] / DECAY_PRECISION;
+                if rate >= above_floor_min {
+                    above_floor += step;
+                    last_rate = rate;
+                }
+            }
+        }
+
+        // first_rate * (1 - f^n) / (1 - f), with 1 - f = decay_bps / 10000
+        let series = first_rate
+            .checked_mul(DECAY_PRECISION - decay_factor(decay_bps, above_floor))
+            .ok_or(BotanikaError::Overflow)?
+            / DECAY_PRECISION
+            * 10000
+            / decay_bps as u128
+            / DECAY_PRECISION;
+        let floored = (floor_rate as u128) * (periods - above_floor) as u128;
+        series.checked_add(floored).ok_or_else(|| error!(BotanikaError::Overflow))
+    }
+
+    /// Roll a time-weighted average balance forward by `elapsed` seconds during which `balance`
+    /// was held. After a full `window` the average equals the balance.
+    pub fn calculate_time_weighted_balance(
+        previous_average: u64,
+        balance: u64,
+        elapsed: i64,
+        window: i64,
+    ) -> Result<u64> {
+        if window <= 0 || elapsed >= window {
+            return Ok(balance);
+        }
+        let elapsed = elapsed.max(0) as u128;
+        let window = window as u128;
+        let carried = (previous_average as u128)
+            .checked_mul(window - elapsed)
+            .ok_or(BotanikaError::Overflow)?;
+        let added = (balance as u128)
+            .checked_mul(elapsed)
+            .ok_or(BotanikaError::Overflow)?;
+        let average = carried
+            .checked_add(added)
+            .ok_or(BotanikaError::Overflow)?
+            .checked_div(window)
+            .ok_or(BotanikaError::Overflow)?;
+        u64::try_from(average).map_err(|_| error!(BotanikaError::Overflow))
+    }
+
+    /// Calculate the loyalty multiplier for a position staked continuously for `staked_for`
+    /// seconds: it grows by `bps_per_period` every `period`, linearly, up to `max_bps`
+    pub fn calculate_loyalty_multiplier(
+        staked_for: i64,
+        bps_per_period: u64,
+        period: i64,
+        max_bps: u64,
+    ) -> Result<u64> {
+        if staked_for <= 0 || period <= 0 {
+            return Ok(BASE_MULTIPLIER);
+        }
+        let boost_bps = (staked_for as u128)
+            .checked_mul(bps_per_period as u128)
+            .ok_or(BotanikaError::Overflow)?
+            .checked_div(period as u128)
+            .ok_or(BotanikaError::Overflow)?
+            .min(max_bps as u128) as u64;
+        safe_add(BASE_MULTIPLIER, boost_bps * BASE_MULTIPLIER / 10000)
+    }
+
+    /// Calculate staking level from amount: the highest tier whose threshold, in whole tokens
+    /// scaled by `decimals`, is met. Thresholds too large to represent are never met.
+    pub fn calculate_staking_level(amount: u64, tiers: &[LevelTier], decimals: u8) -> u8 {
+        let scale = 10u128.checked_pow(decimals as u32);
+        let mut level = 0;
+        for (index, tier) in tiers.iter().enumerate() {
+            let threshold = scale.and_then(|scale| (tier.min_amount as u128).checked_mul(scale));
+            match threshold {
+                Some(threshold) if amount as u128 >= threshold => level = index as u8,
+                _ => break,
+            }
+        }
+        level
+    }
+} 
+#[cfg(test)]
+mod tests {
+    use super::math::*;
+    use crate::shared_types::*;
+
+    const WEEK: i64 = 7 * 24 * 60 * 60;
+
+    #[test]
+    fn time_weighted_balance_moves_linearly_over_the_window() {
+        assert_eq!(calculate_time_weighted_balance(0, 1_000, 0, WEEK).unwrap(), 0);
+        assert_eq!(calculate_time_weighted_balance(0, 1_000, WEEK / 2, WEEK).unwrap(), 500);
+        assert_eq!(calculate_time_weighted_balance(1_000, 0, WEEK / 4, WEEK).unwrap(), 750);
+        // Clock going backwards keeps the previous average
+        assert_eq!(calculate_time_weighted_balance(400, 1_000, -5, WEEK).unwrap(), 400);
+        // Rounds down
+        assert_eq!(calculate_time_weighted_balance(0, 10, 1, 3).unwrap(), 3);
+    }
+
+    #[test]
+    fn time_weighted_balance_window_edges() {
+        assert_eq!(calculate_time_weighted_balance(0, 1_000, WEEK, WEEK).unwrap(), 1_000);
+        assert_eq!(calculate_time_weighted_balance(0, 1_000, WEEK + 1, WEEK).unwrap(), 1_000);
+        assert_eq!(calculate_time_weighted_balance(5, 1_000, 1, 0).unwrap(), 1_000);
+        assert_eq!(calculate_time_weighted_balance(u64::MAX, u64::MAX, 1, WEEK).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn loyalty_multiplier_ramps_up_to_the_cap() {
+        let ramp = |staked_for| {
+            calculate_loyalty_multiplier(staked_for, LOYALTY_BPS_PER_PERIOD, LOYALTY_PERIOD, MAX_LOYALTY_BPS).unwrap()
+        };
+        assert_eq!(ramp(0), BASE_MULTIPLIER);
+        assert_eq!(ramp(-10), BASE_MULTIPLIER);
+        // Less than a basis point of boost rounds away
+        assert_eq!(ramp(1), BASE_MULTIPLIER);
+        assert_eq!(ramp(LOYALTY_PERIOD / 2), 1_025);
+        assert_eq!(ramp(LOYALTY_PERIOD), 1_050);
+        assert_eq!(ramp(10 * LOYALTY_PERIOD), 1_500);
+        assert_eq!(ramp(i64::MAX), 1_500);
+        assert_eq!(calculate_loyalty_multiplier(LOYALTY_PERIOD, 500, 0, 5000).unwrap(), BASE_MULTIPLIER);
+    }
+
+    #[test]
+    fn early_unstake_penalty_shrinks_with_the_remaining_lockup() {
+        assert_eq!(calculate_early_unstake_penalty(1_000, 5000, WEEK, WEEK).unwrap(), 500);
+        assert_eq!(calculate_early_unstake_penalty(1_000, 5000, WEEK / 2, WEEK).unwrap(), 250);
+        // Remaining time beyond the lock duration is capped
+        assert_eq!(calculate_early_unstake_penalty(1_000, 5000, 2 * WEEK, WEEK).unwrap(), 500);
+        assert_eq!(calculate_early_unstake_penalty(1_000, 5000, 0, WEEK).unwrap(), 0);
+        assert_eq!(calculate_early_unstake_penalty(1_000, 5000, -1, WEEK).unwrap(), 0);
+        assert_eq!(calculate_early_unstake_penalty(1_000, 5000, WEEK, 0).unwrap(), 0);
+        // Rounds down in the user's favour
+        assert_eq!(calculate_early_unstake_penalty(3, 5000, 1, 2).unwrap(), 0);
+        assert_eq!(calculate_early_unstake_penalty(u64::MAX, 10000, WEEK, WEEK).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn exchange_rate_starts_at_one() {
+        assert_eq!(calculate_exchange_rate(0, 0).unwrap(), EXCHANGE_RATE_PRECISION);
+        assert_eq!(calculate_exchange_rate(1_000, 0).unwrap(), EXCHANGE_RATE_PRECISION);
+        assert_eq!(calculate_exchange_rate(0, 1_000).unwrap(), EXCHANGE_RATE_PRECISION);
+        assert_eq!(calculate_exchange_rate(2_000, 1_000).unwrap(), 2 * EXCHANGE_RATE_PRECISION);
+        assert_eq!(calculate_exchange_rate(1, 3).unwrap(), EXCHANGE_RATE_PRECISION / 3);
+    }
+
+    #[test]
+    fn receipt_amount_follows_the_rate() {
+        assert_eq!(calculate_receipt_amount(1_000, 0, 0).unwrap(), 1_000);
+        assert_eq!(calculate_receipt_amount(1_000, 5_000, 0).unwrap(), 1_000);
+        assert_eq!(calculate_receipt_amount(1_000, 0, 5_000).unwrap(), 1_000);
+        assert_eq!(calculate_receipt_amount(1_000, 2_000, 1_000).unwrap(), 500);
+        // Rounds down in favour of existing holders
+        assert_eq!(calculate_receipt_amount(1, 3, 2).unwrap(), 0);
+        assert_eq!(calculate_receipt_amount(2, 3, 2).unwrap(), 1);
+        assert!(calculate_receipt_amount(u64::MAX, 1, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn receipt_redemption_follows_the_rate_and_rounds_up() {
+        assert_eq!(calculate_receipt_redemption(250, 1_000, 1_000).unwrap(), 250);
+        // At 2.0 stake tokens per receipt half as many receipts are burned
+        assert_eq!(calculate_receipt_redemption(500, 2_000, 1_000).unwrap(), 250);
+        assert_eq!(calculate_receipt_redemption(1, 3, 2).unwrap(), 1);
+        assert_eq!(calculate_receipt_redemption(0, 3, 2).unwrap(), 0);
+        assert_eq!(calculate_receipt_redemption(1, 0, 7).unwrap(), 7);
+        assert!(calculate_receipt_redemption(u64::MAX, 1, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn staking_level_scales_thresholds_by_decimals() {
+        let tiers = &DEFAULT_LEVEL_TIERS[..4];
+        assert_eq!(calculate_staking_level(0, tiers, 0), 0);
+        assert_eq!(calculate_staking_level(999, tiers, 0), 0);
+        assert_eq!(calculate_staking_level(1_000, tiers, 0), 1);
+        assert_eq!(calculate_staking_level(4_999, tiers, 0), 1);
+        assert_eq!(calculate_staking_level(5_000, tiers, 0), 2);
+        assert_eq!(calculate_staking_level(u64::MAX, tiers, 0), 3);
+        assert_eq!(calculate_staking_level(1_000, tiers, 9), 0);
+        assert_eq!(calculate_staking_level(1_000_000_000_000 - 1, tiers, 9), 0);
+        assert_eq!(calculate_staking_level(1_000_000_000_000, tiers, 9), 1);
+        // Thresholds that cannot be represented are never met
+        assert_eq!(calculate_staking_level(u64::MAX, tiers, 40), 0);
+        assert_eq!(calculate_staking_level(u64::MAX, &[], 0), 0);
+    }
+
+    #[test]
+    fn decayed_rate_halves_each_period_down_to_the_floor() {
+        assert_eq!(calculate_decayed_rate(1_000_000, 5000, 0, 0).unwrap(), 1_000_000);
+        assert_eq!(calculate_decayed_rate(1_000_000, 5000, 1, 0).unwrap(), 500_000);
+        assert_eq!(calculate_decayed_rate(1_000_000, 5000, 3, 0).unwrap(), 125_000);
+        assert_eq!(calculate_decayed_rate(1_000_000, 5000, 3, 200_000).unwrap(), 200_000);
+        assert_eq!(calculate_decayed_rate(1_000_000, 10000, 1, 7).unwrap(), 7);
+        assert_eq!(calculate_decayed_rate(1_000_000, 5000, u64::MAX, 3).unwrap(), 3);
+        assert!(calculate_decayed_rate(1_000_000, 10001, 1, 0).is_err());
+    }
+
+    fn decayed_emission_one_by_one(initial: u64, decay_bps: u64, first: u64, periods: u64, floor: u64) -> u128 {
+        (first..first + periods)
+            .map(|period| calculate_decayed_rate(initial, decay_bps, period, floor).unwrap() as u128)
+            .sum()
+    }
+
+    #[test]
+    fn decayed_emission_matches_period_by_period_sum() {
+        // Within each case the floor is crossed part way through the range
+        for (initial, decay_bps, first, periods, floor) in [
+            (1_000_000_000_000, 5000, 0, 64, 0),
+            (1_000_000_000_000, 5000, 3, 20, 1_000_000),
+            (1_000_000, 100, 0, 500, 400_000),
+            (1_000_000, 1, 10, 1000, 0),
+            (123_456_789, 2500, 7, 40, 1),
+        ] {
+            let expected = decayed_emission_one_by_one(initial, decay_bps, first, periods, floor);
+            let actual = calculate_decayed_emission(initial, decay_bps, first, periods, floor).unwrap();
+            // Closed form skips the per-period rounding down, so it may only come out slightly higher
+            assert!(actual >= expected, "{actual} < {expected}");
+            assert!(actual - expected <= periods as u128 + 10000 / decay_bps as u128, "{actual} vs {expected}");
         }
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn decayed_emission_edges() {
+        assert_eq!(calculate_decayed_emission(1_000_000, 5000, 0, 0, 0).unwrap(), 0);
+        // A single period pays exactly its rate
+        assert_eq!(calculate_decayed_emission(1_000_000, 5000, 0, 1, 0).unwrap(), 1_000_000);
+        // Full decay leaves only the first period above the floor
+        assert_eq!(calculate_decayed_emission(1_000_000, 10000, 0, 5, 10).unwrap(), 1_000_000 + 4 * 10);
+        // Starting at the floor pays the floor throughout
+        assert_eq!(calculate_decayed_emission(1_000, 5000, 100, 50, 10).unwrap(), 50 * 10);
+        // Astronomically many periods finish in a handful of steps; the rate is 0 after the first 20
+        let emission = calculate_decayed_emission(1_000_000, 5000, 0, u64::MAX, 0).unwrap();
+        let expected = decayed_emission_one_by_one(1_000_000, 5000, 0, 20, 0);
+        assert!(emission >= expected && emission - expected <= 20, "{emission} vs {expected}");
+        assert!(calculate_decayed_emission(1_000_000, 0, 0, 1, 0).is_err());
+    }
+}
@@ -1,41 +1,44 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::TokenAccount;
 
 // Shared types for cross-program communication
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum StakingLevel {
-    Bronze,    // 0-1000 BONSAI
-    Silver,    // 1001-5000 BONSAI  
-    Gold,      // 5001-10000 BONSAI
-    Platinum,  // 10001+ BONSAI
+/// A staking level: positions holding at least `min_amount` whole tokens earn `multiplier`.
+/// Levels are identified by their index in the tier table, lowest first.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LevelTier {
+    pub min_amount: u64, // whole tokens, scaled by the stake mint's decimals when compared
+    pub multiplier: u64, // 1000 = 1.0x
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Eq)]
 pub enum StakingStatus {
+    #[default]
     Active,
     Locked,
     Unstaking,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Default)]
 pub enum ProofType {
+    #[default]
     ProofOfStake,      // Staking-based rewards
     ProofOfExecution,   // Computational work
     ProofOfService,     // Node operation
     ProofOfEfficiency,  // Energy efficiency
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Default)]
 pub enum RewardStatus {
+    #[default]
     Pending,
     Available,
     Claimed,
     Expired,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Default)]
 pub enum ProposalStatus {
+    #[default]
     Draft,
     Active,
     Approved,
@@ -44,18 +47,71 @@ pub enum ProposalStatus {
     Expired,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Default)]
 pub enum ProposalType {
+    #[default]
     MultiplierChange,
     FeeStructure,
     RewardPool,
     EmergencyAction,
 }
 
+/// Where early unstake penalties are sent
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PenaltyDestination {
+    Burn,
+    #[default]
+    Treasury,
+    Redistribute, // Paid to remaining stakers through the reward pool
+}
+
+/// A selectable lockup duration and the reward boost it earns
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LockTier {
+    pub duration: i64,   // seconds
+    pub multiplier: u64, // 1000 = 1.0x
+}
+
+/// How a staking pool's main reward rate changes over time
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmissionCurve {
+    /// `reward_rate_per_slot` for as long as the pool runs
+    #[default]
+    Flat,
+    /// A fixed list of rates, each applying from its segment's start slot
+    Segments,
+    /// A rate that shrinks by `decay_bps` every period, down to a floor
+    Decay,
+}
+
+/// Rate applying from `start_slot` until the next segment starts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq)]
+pub struct RateSegment {
+    pub start_slot: u64,
+    pub rate_per_slot: u64,
+}
+
+/// Exponential decay curve; a `decay_bps` of 5000 halves the rate every `period_slots`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq)]
+pub struct DecayCurve {
+    pub start_slot: u64,
+    pub period_slots: u64,
+    pub initial_rate_per_slot: u64,
+    pub decay_bps: u64,
+    pub floor_rate_per_slot: u64,
+}
+
 // Shared constants
 pub const LOCKUP_PERIOD: i64 = 7 * 24 * 60 * 60; // 7 days
 pub const CLAIM_COOLDOWN: i64 = 24 * 60 * 60;     // 24 hours
+pub const UNBONDING_PERIOD: i64 = 3 * 24 * 60 * 60; // 3 days
 pub const PROPOSAL_DURATION: i64 = 3 * 24 * 60 * 60; // 3 days
+pub const MAX_LOCKUP_PERIOD: i64 = 365 * 24 * 60 * 60; // 1 year
+pub const MAX_CLAIM_COOLDOWN: i64 = 30 * 24 * 60 * 60; // 30 days
+pub const LEVEL_WINDOW: i64 = 7 * 24 * 60 * 60; // 7 days
+pub const MAX_LEVEL_WINDOW: i64 = 90 * 24 * 60 * 60; // 90 days
+pub const SWEEP_INACTIVITY_PERIOD: i64 = 30 * 24 * 60 * 60; // 30 days
+pub const MAX_REWARD_RATE_PER_SLOT: u64 = 1_000_000_000_000; // in reward token base units
 
 pub const BASE_MULTIPLIER: u64 = 1000; // 1.0x
 pub const BRONZE_MULTIPLIER: u64 = 1200; // 1.2x
@@ -63,16 +119,59 @@ pub const SILVER_MULTIPLIER: u64 = 1500; // 1.5x
 pub const GOLD_MULTIPLIER: u64 = 2000;   // 2.0x
 pub const PLATINUM_MULTIPLIER: u64 = 3000; // 3.0x
 
+pub const MAX_LEVEL_TIERS: usize = 8;
+pub const DEFAULT_LEVEL_TIER_COUNT: u8 = 4;
+pub const DEFAULT_LEVEL_TIERS: [LevelTier; MAX_LEVEL_TIERS] = [
+    LevelTier { min_amount: 0, multiplier: BRONZE_MULTIPLIER },        // Bronze
+    LevelTier { min_amount: 1_000, multiplier: SILVER_MULTIPLIER },    // Silver
+    LevelTier { min_amount: 5_000, multiplier: GOLD_MULTIPLIER },      // Gold
+    LevelTier { min_amount: 10_000, multiplier: PLATINUM_MULTIPLIER }, // Platinum
+    LevelTier { min_amount: 0, multiplier: 0 },
+    LevelTier { min_amount: 0, multiplier: 0 },
+    LevelTier { min_amount: 0, multiplier: 0 },
+    LevelTier { min_amount: 0, multiplier: 0 },
+];
+
+/// Fixed-point scale of the staking reward-per-share accumulator
+pub const ACC_REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// Maximum number of partner reward streams per staking pool
+pub const MAX_REWARD_STREAMS: usize = 4;
+
+/// Maximum number of rate segments in a staking pool's emission schedule
+pub const MAX_RATE_SEGMENTS: usize = 8;
+
+/// Shortest decay period a staking pool's emission schedule may use, about a day of slots
+pub const MIN_DECAY_PERIOD_SLOTS: u64 = 216_000;
+
+/// Fixed-point scale of the stBONSAI exchange rate (stake tokens per receipt token)
+pub const EXCHANGE_RATE_PRECISION: u128 = 1_000_000_000;
+
+pub const LOCK_TIER_COUNT: usize = 5;
+pub const DEFAULT_LOCK_TIERS: [LockTier; LOCK_TIER_COUNT] = [
+    LockTier { duration: 7 * 24 * 60 * 60, multiplier: 1000 },   // 7 days, 1.0x
+    LockTier { duration: 30 * 24 * 60 * 60, multiplier: 1100 },  // 30 days, 1.1x
+    LockTier { duration: 90 * 24 * 60 * 60, multiplier: 1250 },  // 90 days, 1.25x
+    LockTier { duration: 180 * 24 * 60 * 60, multiplier: 1500 }, // 180 days, 1.5x
+    LockTier { duration: 365 * 24 * 60 * 60, multiplier: 2000 }, // 365 days, 2.0x
+];
+
 pub const STAKING_FEE_BPS: u64 = 50;    // 0.5%
 pub const UNSTAKING_FEE_BPS: u64 = 100;  // 1.0%
 pub const CLAIM_FEE_BPS: u64 = 25;       // 0.25%
+pub const EARLY_UNSTAKE_PENALTY_BPS: u64 = 1000; // 10% at the start of the lockup
+
+pub const LOYALTY_BPS_PER_PERIOD: u64 = 500; // +5% per period of continuous staking
+pub const LOYALTY_PERIOD: i64 = 30 * 24 * 60 * 60; // 30 days
+pub const MAX_LOYALTY_BPS: u64 = 5000; // capped at +50%
+pub const LOYALTY_BPS_LIMIT: u64 = 10000; // governance may not set a cap above +100%
 
 // Shared account structures for cross-program calls
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct StakingInfo {
     pub user: Pubkey,
     pub amount: u64,
-    pub level: StakingLevel,
+    pub level: u8, // index into the level tier table
     pub status: StakingStatus,
     pub staked_at: i64,
     pub last_claimed_at: i64,
@@ -88,10 +187,65 @@ pub struct RewardInfo {
     pub claimed_at: Option<i64>,
 }
 
+/// A stake position, as returned by the staking program's `get_position` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PositionInfo {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub position_id: u64,
+    pub amount: u64,
+    pub weighted_amount: u64,
+    pub multiplier: u64, // level x lock tier x loyalty at the current time, 1000 = 1.0x
+    pub level: u8,
+    pub status: StakingStatus,
+    pub lock_tier: u8,
+    pub lockup_end: i64,
+    pub unbonding_amount: u64,
+    pub receipt_amount: u64,
+    pub staked_at: i64,
+    pub last_claimed_at: i64,
+}
+
+/// Rewards a position could claim at `slot`, as returned by `get_pending_rewards`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PendingRewardsInfo {
+    pub owner: Pubkey,
+    pub position_id: u64,
+    pub pending_rewards: u64,
+    pub claim_fee: u64,
+    pub claimable: u64,
+    pub stream_rewards: Vec<u64>, // indexed like the pool's reward streams
+    pub next_claim_at: i64,
+    pub slot: u64,
+}
+
+/// Pool totals at `slot`, as returned by `get_pool_stats`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PoolStats {
+    pub pool: Pubkey,
+    pub staking_mint: Pubkey,
+    pub pool_id: u64,
+    pub reward_mint: Pubkey,
+    pub total_staked: u64,
+    pub total_weighted_stake: u64,
+    pub total_unbonding: u64,
+    pub reward_rate_per_slot: u64, // current rate under the emission schedule
+    pub acc_reward_per_share: u128,
+    pub total_funded: u64,
+    pub total_rewards_owed: u64,
+    pub total_rewards_paid: u64,
+    pub total_emitted: u64,
+    pub runway_slots: u64,
+    pub exchange_rate: u128, // stake tokens per receipt token, scaled by EXCHANGE_RATE_PRECISION
+    pub reward_stream_count: u8,
+    pub paused: bool,
+    pub slot: u64,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct GovernanceInfo {
     pub authority: Pubkey,
-    pub multipliers: [u64; 4], // Bronze, Silver, Gold, Platinum
+    pub level_tiers: Vec<LevelTier>,
     pub last_updated: i64,
 }
 
@@ -118,4 +272,18 @@ pub enum BotanikaError {
     Overflow,
     #[msg("Invalid authority")]
     InvalidAuthority,
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Invalid multiplier value")]
+    InvalidMultiplier,
+    #[msg("Lockup period out of range")]
+    InvalidLockupPeriod,
+    #[msg("Claim cooldown out of range")]
+    InvalidClaimCooldown,
+    #[msg("Reward rate out of range")]
+    InvalidRewardRate,
+    #[msg("Level window out of range")]
+    InvalidLevelWindow,
+    #[msg("Invalid loyalty boost")]
+    InvalidLoyaltyBoost,
 } 
\ No newline at end of file
@@ -11,12 +11,10 @@ pub mod validation {
         Ok(())
     }
 
-    /// Validate staking level
-    pub fn validate_staking_level(level: StakingLevel) -> Result<()> {
-        match level {
-            StakingLevel::Bronze | StakingLevel::Silver | 
-            StakingLevel::Gold | StakingLevel::Platinum => Ok(()),
-        }
+    /// Validate staking level against the number of configured tiers
+    pub fn validate_staking_level(level: u8, tier_count: u8) -> Result<()> {
+        require!(level < tier_count, BotanikaError::InvalidStakingLevel);
+        Ok(())
     }
 
     /// Validate multiplier value
@@ -26,6 +24,57 @@ pub mod validation {
         Ok(())
     }
 
+    /// Validate a level tier table: the first tier starts at zero, thresholds strictly
+    /// increase and higher levels may not earn a lower multiplier
+    pub fn validate_level_tiers(tiers: &[LevelTier]) -> Result<()> {
+        require!(!tiers.is_empty(), BotanikaError::InvalidStakingLevel);
+        require!(tiers.len() <= MAX_LEVEL_TIERS, BotanikaError::InvalidStakingLevel);
+        require!(tiers[0].min_amount == 0, BotanikaError::InvalidStakingLevel);
+        for tier in tiers {
+            validate_multiplier(tier.multiplier)?;
+        }
+        for pair in tiers.windows(2) {
+            require!(pair[0].min_amount < pair[1].min_amount, BotanikaError::InvalidStakingLevel);
+            require!(pair[0].multiplier <= pair[1].multiplier, BotanikaError::InvalidMultiplier);
+        }
+        Ok(())
+    }
+
+    /// Validate lockup period
+    pub fn validate_lockup_period(lockup_period: i64) -> Result<()> {
+        require!(lockup_period >= 0, BotanikaError::InvalidLockupPeriod);
+        require!(lockup_period <= MAX_LOCKUP_PERIOD, BotanikaError::InvalidLockupPeriod);
+        Ok(())
+    }
+
+    /// Validate claim cooldown
+    pub fn validate_claim_cooldown(claim_cooldown: i64) -> Result<()> {
+        require!(claim_cooldown >= 0, BotanikaError::InvalidClaimCooldown);
+        require!(claim_cooldown <= MAX_CLAIM_COOLDOWN, BotanikaError::InvalidClaimCooldown);
+        Ok(())
+    }
+
+    /// Validate the time-weighted level window
+    pub fn validate_level_window(level_window: i64) -> Result<()> {
+        require!(level_window > 0, BotanikaError::InvalidLevelWindow);
+        require!(level_window <= MAX_LEVEL_WINDOW, BotanikaError::InvalidLevelWindow);
+        Ok(())
+    }
+
+    /// Validate the loyalty boost ramp and its cap
+    pub fn validate_loyalty_boost(bps_per_period: u64, period: i64, max_bps: u64) -> Result<()> {
+        require!(period > 0, BotanikaError::InvalidLoyaltyBoost);
+        require!(bps_per_period <= max_bps, BotanikaError::InvalidLoyaltyBoost);
+        require!(max_bps <= LOYALTY_BPS_LIMIT, BotanikaError::InvalidLoyaltyBoost);
+        Ok(())
+    }
+
+    /// Validate reward rate per slot
+    pub fn validate_reward_rate(reward_rate_per_slot: u64) -> Result<()> {
+        require!(reward_rate_per_slot <= MAX_REWARD_RATE_PER_SLOT, BotanikaError::InvalidRewardRate);
+        Ok(())
+    }
+
     /// Validate proof type
     pub fn validate_proof_type(proof_type: ProofType) -> Result<()> {
         match proof_type {
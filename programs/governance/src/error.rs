@@ -31,4 +31,10 @@ pub enum GovernanceError {
     
     #[msg("Invalid fee structure")]
     InvalidFeeStructure,
+    
+    #[msg("Invalid lock tier")]
+    InvalidLockTier,
+    
+    #[msg("Invalid early unstake penalty")]
+    InvalidPenalty,
 } 
\ No newline at end of file
@@ -7,7 +7,7 @@ use botanika_common::*;
 use crate::state::*;
 use crate::error::GovernanceError;
 
-declare_id!("Govrnce1111111111111111111111111111111111");
+declare_id!("GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw");
 
 #[program]
 pub mod botanika_governance {
@@ -16,14 +16,29 @@ pub mod botanika_governance {
     pub fn initialize_governance(ctx: Context<InitializeGovernance>) -> Result<()> {
         let governance_state = &mut ctx.accounts.governance_state;
         governance_state.authority = ctx.accounts.authority.key();
-        governance_state.multipliers = [1200, 1500, 2000, 3000]; // Bronze, Silver, Gold, Platinum
+        governance_state.level_tiers = DEFAULT_LEVEL_TIERS; // Bronze, Silver, Gold, Platinum
+        governance_state.level_tier_count = DEFAULT_LEVEL_TIER_COUNT;
         governance_state.staking_fee_bps = 50;    // 0.5%
         governance_state.unstaking_fee_bps = 100; // 1.0%
         governance_state.claim_fee_bps = 25;      // 0.25%
+        governance_state.early_unstake_penalty_bps = EARLY_UNSTAKE_PENALTY_BPS;
+        governance_state.penalty_destination = PenaltyDestination::Treasury;
+        governance_state.lock_tiers = DEFAULT_LOCK_TIERS;
+        governance_state.lockup_period = LOCKUP_PERIOD;
+        governance_state.claim_cooldown = CLAIM_COOLDOWN;
+        governance_state.loyalty_bps_per_period = LOYALTY_BPS_PER_PERIOD;
+        governance_state.loyalty_period = LOYALTY_PERIOD;
+        governance_state.max_loyalty_bps = MAX_LOYALTY_BPS;
+        governance_state.emergency_paused = false;
         governance_state.proposal_duration = 3 * 24 * 60 * 60; // 3 days
         governance_state.quorum_threshold = 1000; // Minimum votes
         governance_state.last_updated = Clock::get()?.unix_timestamp;
-        governance_state.bump = *ctx.bumps.get("governance_state").unwrap();
+        governance_state.bump = ctx.bumps.governance_state;
+
+        emit_cpi!(GovernanceInitialized {
+            authority: governance_state.authority,
+            timestamp: governance_state.last_updated,
+        });
         Ok(())
     }
 
@@ -32,12 +47,134 @@ pub mod botanika_governance {
         level: u8,
         multiplier: u64,
     ) -> Result<()> {
-        require!(level < 4, GovernanceError::InvalidMultiplier);
+        let governance_state = &mut ctx.accounts.governance_state;
+        require!(level < governance_state.level_tier_count, GovernanceError::InvalidMultiplier);
         require!(multiplier > 0, GovernanceError::InvalidMultiplier);
         
+        let old_multiplier = governance_state.level_tiers[level as usize].multiplier;
+        governance_state.level_tiers[level as usize].multiplier = multiplier;
+        validate_level_tiers(governance_state.active_level_tiers())?;
+        governance_state.last_updated = Clock::get()?.unix_timestamp;
+
+        emit_cpi!(MultiplierSet {
+            authority: ctx.accounts.authority.key(),
+            level,
+            old_multiplier,
+            new_multiplier: multiplier,
+            timestamp: governance_state.last_updated,
+        });
+        Ok(())
+    }
+
+    pub fn set_level_tiers(
+        ctx: Context<SetLevelTiers>,
+        tiers: Vec<LevelTier>,
+    ) -> Result<()> {
+        validate_level_tiers(&tiers)?;
+        
+        let governance_state = &mut ctx.accounts.governance_state;
+        governance_state.level_tiers = [LevelTier::default(); MAX_LEVEL_TIERS];
+        governance_state.level_tiers[..tiers.len()].copy_from_slice(&tiers);
+        governance_state.level_tier_count = tiers.len() as u8;
+        governance_state.last_updated = Clock::get()?.unix_timestamp;
+
+        emit_cpi!(LevelTiersSet {
+            authority: ctx.accounts.authority.key(),
+            level_tiers: tiers,
+            timestamp: governance_state.last_updated,
+        });
+        Ok(())
+    }
+
+    pub fn set_lock_tier(
+        ctx: Context<SetLockTier>,
+        index: u8,
+        duration: i64,
+        multiplier: u64,
+    ) -> Result<()> {
+        require!((index as usize) < LOCK_TIER_COUNT, GovernanceError::InvalidLockTier);
+        require!(duration > 0, GovernanceError::InvalidLockTier);
+        validate_lockup_period(duration)?;
+        validate_multiplier(multiplier)?;
+        
+        let governance_state = &mut ctx.accounts.governance_state;
+        governance_state.lock_tiers[index as usize] = LockTier { duration, multiplier };
+        governance_state.last_updated = Clock::get()?.unix_timestamp;
+
+        emit_cpi!(LockTierSet {
+            authority: ctx.accounts.authority.key(),
+            index,
+            duration,
+            multiplier,
+            timestamp: governance_state.last_updated,
+        });
+        Ok(())
+    }
+
+    pub fn set_staking_periods(
+        ctx: Context<SetStakingPeriods>,
+        lockup_period: i64,
+        claim_cooldown: i64,
+    ) -> Result<()> {
+        validate_lockup_period(lockup_period)?;
+        validate_claim_cooldown(claim_cooldown)?;
+        
+        let governance_state = &mut ctx.accounts.governance_state;
+        governance_state.lockup_period = lockup_period;
+        governance_state.claim_cooldown = claim_cooldown;
+        governance_state.last_updated = Clock::get()?.unix_timestamp;
+
+        emit_cpi!(StakingPeriodsSet {
+            authority: ctx.accounts.authority.key(),
+            lockup_period,
+            claim_cooldown,
+            timestamp: governance_state.last_updated,
+        });
+        Ok(())
+    }
+
+    pub fn set_loyalty_boost(
+        ctx: Context<SetLoyaltyBoost>,
+        bps_per_period: u64,
+        period: i64,
+        max_bps: u64,
+    ) -> Result<()> {
+        validate_loyalty_boost(bps_per_period, period, max_bps)?;
+        
+        let governance_state = &mut ctx.accounts.governance_state;
+        governance_state.loyalty_bps_per_period = bps_per_period;
+        governance_state.loyalty_period = period;
+        governance_state.max_loyalty_bps = max_bps;
+        governance_state.last_updated = Clock::get()?.unix_timestamp;
+
+        emit_cpi!(LoyaltyBoostSet {
+            authority: ctx.accounts.authority.key(),
+            bps_per_period,
+            period,
+            max_bps,
+            timestamp: governance_state.last_updated,
+        });
+        Ok(())
+    }
+
+    pub fn set_early_unstake_penalty(
+        ctx: Context<SetEarlyUnstakePenalty>,
+        penalty_bps: u64,
+        destination: PenaltyDestination,
+    ) -> Result<()> {
+        require!(penalty_bps <= 10000, GovernanceError::InvalidPenalty);
+        
         let governance_state = &mut ctx.accounts.governance_state;
-        governance_state.multipliers[level as usize] = multiplier;
+        governance_state.early_unstake_penalty_bps = penalty_bps;
+        governance_state.penalty_destination = destination;
         governance_state.last_updated = Clock::get()?.unix_timestamp;
+
+        emit_cpi!(EarlyUnstakePenaltySet {
+            authority: ctx.accounts.authority.key(),
+            penalty_bps,
+            destination,
+            timestamp: governance_state.last_updated,
+        });
         Ok(())
     }
 
@@ -49,7 +186,7 @@ pub mod botanika_governance {
         let proposal = &mut ctx.accounts.proposal;
         let governance_state = &ctx.accounts.governance_state;
         
-        proposal.id = governance_state.last_updated; // Simple ID generation
+        proposal.id = governance_state.last_updated as u64; // Simple ID generation
         proposal.proposal_type = proposal_type;
         proposal.status = ProposalStatus::Active;
         proposal.data = data;
@@ -57,8 +194,15 @@ pub mod botanika_governance {
         proposal.voting_ends_at = proposal.created_at + governance_state.proposal_duration;
         proposal.votes_for = 0;
         proposal.votes_against = 0;
-        proposal.bump = *ctx.bumps.get("proposal").unwrap();
-        
+        proposal.bump = ctx.bumps.proposal;
+
+        emit_cpi!(ChangeProposed {
+            proposal_id: proposal.id,
+            proposer: ctx.accounts.proposer.key(),
+            proposal_type: proposal.proposal_type.clone(),
+            voting_ends_at: proposal.voting_ends_at,
+            timestamp: proposal.created_at,
+        });
         Ok(())
     }
 
@@ -80,6 +224,14 @@ pub mod botanika_governance {
                 // Parse and apply fee structure changes
                 // Implementation would depend on data format
             },
+            ProposalType::EmergencyAction => {
+                // data[0]: 1 pauses staking, 0 lifts the pause
+                match proposal.data.first() {
+                    Some(0) => governance_state.emergency_paused = false,
+                    Some(1) => governance_state.emergency_paused = true,
+                    _ => return err!(GovernanceError::InvalidProposalData),
+                }
+            },
             _ => {
                 // Handle other proposal types
             }
@@ -87,12 +239,21 @@ pub mod botanika_governance {
         
         proposal.mark_executed();
         governance_state.last_updated = Clock::get()?.unix_timestamp;
-        
+
+        emit_cpi!(ProposalExecuted {
+            proposal_id: proposal.id,
+            proposal_type: proposal.proposal_type.clone(),
+            authority: ctx.accounts.authority.key(),
+            votes_for: proposal.votes_for,
+            votes_against: proposal.votes_against,
+            timestamp: governance_state.last_updated,
+        });
         Ok(())
     }
 }
 
 #[derive(Accounts)]
+#[event_cpi]
 pub struct InitializeGovernance<'info> {
     #[account(
         init,
@@ -108,6 +269,7 @@ pub struct InitializeGovernance<'info> {
 }
 
 #[derive(Accounts)]
+#[event_cpi]
 pub struct SetMultiplier<'info> {
     #[account(mut, seeds = [b"governance-state"], bump = governance_state.bump, has_one = authority)]
     pub governance_state: Account<'info, GovernanceState>,
@@ -115,6 +277,47 @@ pub struct SetMultiplier<'info> {
 }
 
 #[derive(Accounts)]
+#[event_cpi]
+pub struct SetLevelTiers<'info> {
+    #[account(mut, seeds = [b"governance-state"], bump = governance_state.bump, has_one = authority)]
+    pub governance_state: Account<'info, GovernanceState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct SetLockTier<'info> {
+    #[account(mut, seeds = [b"governance-state"], bump = governance_state.bump, has_one = authority)]
+    pub governance_state: Account<'info, GovernanceState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct SetStakingPeriods<'info> {
+    #[account(mut, seeds = [b"governance-state"], bump = governance_state.bump, has_one = authority)]
+    pub governance_state: Account<'info, GovernanceState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct SetLoyaltyBoost<'info> {
+    #[account(mut, seeds = [b"governance-state"], bump = governance_state.bump, has_one = authority)]
+    pub governance_state: Account<'info, GovernanceState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct SetEarlyUnstakePenalty<'info> {
+    #[account(mut, seeds = [b"governance-state"], bump = governance_state.bump, has_one = authority)]
+    pub governance_state: Account<'info, GovernanceState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
 pub struct ProposeChange<'info> {
     #[account(seeds = [b"governance-state"], bump = governance_state.bump)]
     pub governance_state: Account<'info, GovernanceState>,
@@ -126,6 +329,7 @@ pub struct ProposeChange<'info> {
 }
 
 #[derive(Accounts)]
+#[event_cpi]
 pub struct ExecuteProposal<'info> {
     #[account(mut, seeds = [b"governance-state"], bump = governance_state.bump, has_one = authority)]
     pub governance_state: Account<'info, GovernanceState>,
@@ -1,22 +1,6 @@
 use anchor_lang::prelude::*;
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum ProposalStatus {
-    Draft,
-    Active,
-    Approved,
-    Rejected,
-    Executed,
-    Expired,
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum ProposalType {
-    MultiplierChange,
-    FeeStructure,
-    RewardPool,
-    EmergencyAction,
-}
+use botanika_common::{LevelTier, LockTier, PenaltyDestination, LOCK_TIER_COUNT, MAX_LEVEL_TIERS};
+pub use botanika_common::{ProposalStatus, ProposalType};
 
 #[account]
 #[derive(Default)]
@@ -24,14 +8,36 @@ pub struct GovernanceState {
     /// Authority that can execute proposals
     pub authority: Pubkey,
     
-    /// Current staking level multipliers
-    pub multipliers: [u64; 4], // Bronze, Silver, Gold, Platinum
+    /// Staking level tiers, lowest first; only the first `level_tier_count` are in use
+    pub level_tiers: [LevelTier; MAX_LEVEL_TIERS],
+    pub level_tier_count: u8,
     
     /// Fee structure (in basis points)
     pub staking_fee_bps: u64,
     pub unstaking_fee_bps: u64,
     pub claim_fee_bps: u64,
     
+    /// Early unstake penalty at the start of a lockup (in basis points)
+    pub early_unstake_penalty_bps: u64,
+    
+    /// Where early unstake penalties are sent
+    pub penalty_destination: PenaltyDestination,
+    
+    /// Selectable staking lockup durations and their boosts
+    pub lock_tiers: [LockTier; LOCK_TIER_COUNT],
+    
+    /// Staking minimum lockup and claim cooldown, in seconds
+    pub lockup_period: i64,
+    pub claim_cooldown: i64,
+    
+    /// Staking loyalty boost: `loyalty_bps_per_period` per `loyalty_period` staked, capped at `max_loyalty_bps`
+    pub loyalty_bps_per_period: u64,
+    pub loyalty_period: i64,
+    pub max_loyalty_bps: u64,
+    
+    /// Emergency pause set by an `EmergencyAction` proposal, honoured by the staking program
+    pub emergency_paused: bool,
+    
     /// Proposal settings
     pub proposal_duration: i64,
     pub quorum_threshold: u64,
@@ -46,7 +52,14 @@ pub struct GovernanceState {
 }
 
 impl GovernanceState {
-    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 32;
+    /// The level tiers currently in use
+    pub fn active_level_tiers(&self) -> &[LevelTier] {
+        &self.level_tiers[..self.level_tier_count as usize]
+    }
+}
+
+impl GovernanceState {
+    pub const SIZE: usize = 8 + 32 + 128 + 1 + 8 + 8 + 8 + 8 + 1 + 80 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 1 + 32;
 }
 
 #[account]
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
 
 pub mod state;
 pub mod error;
@@ -8,7 +8,7 @@ use botanika_common::*;
 use crate::state::*;
 use crate::error::RewardsError;
 
-declare_id!("REwArds111111111111111111111111111111111111");
+declare_id!("REWaRDSNYfqD1nVUSdHkTJBbD6JAfUVgzfVbbuC4x7y");
 
 #[program]
 pub mod rewards {
@@ -26,7 +26,15 @@ pub mod rewards {
         reward_pool.reward_vault = reward_vault;
         reward_pool.expiration_period = expiration_period;
         reward_pool.total_rewards_distributed = 0;
-        reward_pool.bump = *ctx.bumps.get("reward_pool").unwrap();
+        reward_pool.bump = ctx.bumps.reward_pool;
+
+        emit_cpi!(RewardPoolInitialized {
+            authority: reward_pool.authority,
+            reward_mint,
+            reward_vault,
+            expiration_period,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
         Ok(())
     }
 
@@ -54,28 +62,71 @@ pub mod rewards {
         reward_recipient.submitted_at = Clock::get()?.unix_timestamp;
         reward_recipient.expires_at = reward_recipient.submitted_at + reward_pool.expiration_period;
         reward_recipient.claimed_at = None;
-        
+
+        emit_cpi!(RewardSubmitted {
+            authority: ctx.accounts.authority.key(),
+            user,
+            amount,
+            proof_type: reward_recipient.proof_type.clone(),
+            expires_at: reward_recipient.expires_at,
+            total_rewards_distributed: reward_pool.total_rewards_distributed,
+            timestamp: reward_recipient.submitted_at,
+        });
         Ok(())
     }
 
-    pub fn claim_reward(ctx: Context<ClaimReward>) -> Result<()> {
-        let reward_recipient = &mut ctx.accounts.reward_recipient;
-        let reward_pool = &ctx.accounts.reward_pool;
+    /// Pay out the caller's available reward from the pool's vault. For a reward mint with a
+    /// transfer hook, pass the hook's accounts in remaining accounts.
+    pub fn claim_reward<'info>(ctx: Context<'_, '_, '_, 'info, ClaimReward<'info>>) -> Result<()> {
+        require!(ctx.accounts.reward_recipient.can_claim(), RewardsError::RewardNotAvailable);
+        require!(ctx.accounts.reward_recipient.user == ctx.accounts.user.key(), RewardsError::InvalidAuthority);
         
-        require!(reward_recipient.can_claim(), RewardsError::RewardNotAvailable);
-        require!(reward_recipient.user == ctx.accounts.user.key(), RewardsError::InvalidAuthority);
-        
-        // Transfer tokens to user
-        token::transfer(ctx.accounts.into_transfer_ctx(), reward_recipient.amount)?;
+        // Transfer tokens to user, signed by the reward pool PDA that owns the vault
+        let bump = [ctx.accounts.reward_pool.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"reward-pool", &bump]];
+        transfer_checked_with_hook(
+            ctx.accounts.into_transfer_ctx().with_signer(signer_seeds).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            ctx.accounts.reward_recipient.amount,
+            ctx.accounts.reward_mint.decimals,
+        )?;
         
         // Mark as claimed
+        let reward_recipient = &mut ctx.accounts.reward_recipient;
         reward_recipient.mark_claimed();
-        
+
+        emit_cpi!(RewardClaimed {
+            user: reward_recipient.user,
+            amount: reward_recipient.amount,
+            proof_type: reward_recipient.proof_type.clone(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
         Ok(())
     }
 }
 
+/// `transfer_checked` that forwards the context's remaining accounts, so Token-2022 transfer hooks can
+/// resolve their extra accounts. They are matched by key and ignored for mints without a hook.
+fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    spl_token_2022::onchain::invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
 #[derive(Accounts)]
+#[event_cpi]
 pub struct InitializeRewardPool<'info> {
     #[account(init, payer = authority, space = 8 + RewardPool::SIZE, seeds = [b"reward-pool"], bump)]
     pub reward_pool: Account<'info, RewardPool>,
@@ -85,10 +136,12 @@ pub struct InitializeRewardPool<'info> {
 }
 
 #[derive(Accounts)]
+#[event_cpi]
+#[instruction(user: Pubkey)]
 pub struct SubmitReward<'info> {
     #[account(mut, seeds = [b"reward-pool"], bump = reward_pool.bump, has_one = authority)]
     pub reward_pool: Account<'info, RewardPool>,
-    #[account(init_if_needed, payer = authority, space = 8 + RewardRecipient::SIZE, seeds = [b"reward-recipient", user.key().as_ref()], bump)]
+    #[account(init_if_needed, payer = authority, space = 8 + RewardRecipient::SIZE, seeds = [b"reward-recipient", user.as_ref()], bump)]
     pub reward_recipient: Account<'info, RewardRecipient>,
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -97,6 +150,7 @@ pub struct SubmitReward<'info> {
 }
 
 #[derive(Accounts)]
+#[event_cpi]
 pub struct ClaimReward<'info> {
     #[account(mut, seeds = [b"reward-pool"], bump = reward_pool.bump)]
     pub reward_pool: Account<'info, RewardPool>,
@@ -104,20 +158,24 @@ pub struct ClaimReward<'info> {
     pub reward_recipient: Account<'info, RewardRecipient>,
     #[account(mut)]
     pub user: Signer<'info>,
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub reward_vault: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    #[account(mut, token::mint = reward_mint)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = reward_pool.reward_vault, token::mint = reward_mint)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = reward_pool.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 impl<'info> ClaimReward<'info> {
-    fn into_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
+    fn into_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
             from: self.reward_vault.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
             to: self.user_token_account.to_account_info(),
             authority: self.reward_pool.to_account_info(),
         };
         CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
     }
 }
+
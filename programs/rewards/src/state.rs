@@ -1,20 +1,5 @@
 use anchor_lang::prelude::*;
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum ProofType {
-    ProofOfStake,      // Staking-based rewards
-    ProofOfExecution,   // Computational work
-    ProofOfService,     // Node operation
-    ProofOfEfficiency,  // Energy efficiency
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum RewardStatus {
-    Pending,
-    Available,
-    Claimed,
-    Expired,
-}
+pub use botanika_common::{ProofType, RewardStatus};
 
 #[account]
 #[derive(Default)]
@@ -4,4 +4,73 @@ use anchor_lang::prelude::*;
 pub enum StakingError {
     #[msg("Not enough time has passed to claim rewards.")]
     TooEarly,
+
+    #[msg("Invalid amount")]
+    InvalidAmount,
+
+    #[msg("Insufficient stake amount")]
+    InsufficientStake,
+
+    #[msg("Lockup period not met")]
+    LockupPeriodNotMet,
+
+    #[msg("Lockup has ended, use unstake instead")]
+    LockupEnded,
+
+    #[msg("Claim cooldown not met")]
+    ClaimCooldownNotMet,
+
+    #[msg("Overflow in calculation")]
+    Overflow,
+
+    #[msg("Position still holds stake, receipts or unclaimed rewards")]
+    PositionNotEmpty,
+
+    #[msg("Position has been active too recently to sweep")]
+    PositionNotAbandoned,
+
+    #[msg("Invalid lock tier")]
+    InvalidLockTier,
+
+    #[msg("Lock tier cannot be shorter than the position's current tier")]
+    LockTierDowngrade,
+
+    #[msg("Unbonding period not met")]
+    UnbondingPeriodNotMet,
+
+    #[msg("Stake and reward mints do not match")]
+    MintMismatch,
+
+    #[msg("No stakers to distribute rewards to")]
+    NoStakers,
+
+    #[msg("Stake is frozen pending review")]
+    StakeFrozen,
+
+    #[msg("Stake is not frozen")]
+    StakeNotFrozen,
+
+    #[msg("Invalid authority")]
+    InvalidAuthority,
+
+    #[msg("Invalid slash percentage")]
+    InvalidSlashAmount,
+
+    #[msg("Staking is paused")]
+    Paused,
+
+    #[msg("Staking is not paused")]
+    NotPaused,
+
+    #[msg("Position transfers are restricted")]
+    TransfersRestricted,
+
+    #[msg("Invalid reward stream")]
+    InvalidRewardStream,
+
+    #[msg("Too many reward streams")]
+    TooManyRewardStreams,
+
+    #[msg("Invalid emission schedule")]
+    InvalidEmissionSchedule,
 }
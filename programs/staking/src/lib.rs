@@ -1,188 +1,2300 @@
 /// File: programs/staking/src/lib.rs
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+use botanika_governance::state::GovernanceState;
 
-pub mod errors;
+pub mod error;
 pub mod state;
 use botanika_common::*;
 
-use crate::errors::StakingError;
+use crate::error::StakingError;
 use crate::state::*;
 
-declare_id!("Stake11111111111111111111111111111111111111");
+declare_id!("StakeSSzfxn391k3LvdKbZP5WVwWd6AsY1DNiXHjQfK");
 
 #[program]
 pub mod staking {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+    /// Create pool `pool_id` for the stake mint. Any number of pools can share a stake mint.
+    pub fn initialize(ctx: Context<Initialize>, pool_id: u64, reward_rate_per_slot: u64) -> Result<()> {
+        validate_reward_rate(reward_rate_per_slot)?;
+
         let staking_state = &mut ctx.accounts.staking_state;
+        staking_state.pool_id = pool_id;
         staking_state.admin = ctx.accounts.authority.key();
-        staking_state.bump = *ctx.bumps.get("staking_state").unwrap();
+        staking_state.reward_authority = ctx.accounts.authority.key();
+        staking_state.slashing_authority = ctx.accounts.authority.key();
+        staking_state.staking_mint = ctx.accounts.stake_mint.key();
+        staking_state.stake_decimals = ctx.accounts.stake_mint.decimals;
+        staking_state.reward_mint = ctx.accounts.reward_mint.key();
+        staking_state.vault = ctx.accounts.vault.key();
+        staking_state.reward_vault = ctx.accounts.reward_vault.key();
+        staking_state.receipt_mint = ctx.accounts.receipt_mint.key();
+        staking_state.reward_rate_per_slot = reward_rate_per_slot;
+        staking_state.treasury = ctx.accounts.treasury.key();
+        staking_state.reward_treasury = ctx.accounts.reward_treasury.key();
+        staking_state.bump = ctx.bumps.staking_state;
         staking_state.total_staked = 0;
-        staking_state.multipliers = [BRONZE_MULTIPLIER, SILVER_MULTIPLIER, GOLD_MULTIPLIER, PLATINUM_MULTIPLIER];
-        staking_state.lockup_period = LOCKUP_PERIOD;
-        staking_state.claim_cooldown = CLAIM_COOLDOWN;
+        staking_state.acc_reward_per_share = 0;
+        staking_state.total_weighted_stake = 0;
+        let clock = Clock::get()?;
+        staking_state.last_update_slot = clock.slot;
+        staking_state.sync_from_governance(&ctx.accounts.governance_state, clock.unix_timestamp)?;
+        staking_state.unbonding_period = UNBONDING_PERIOD;
+        staking_state.level_window = LEVEL_WINDOW;
+        staking_state.total_unbonding = 0;
+        staking_state.total_staking_fees = 0;
+        staking_state.total_unstaking_fees = 0;
+        staking_state.total_claim_fees = 0;
+        staking_state.total_penalties = 0;
+        staking_state.total_slashed = 0;
+        staking_state.total_rewards_owed = 0;
+        staking_state.total_funded = 0;
+        staking_state.total_rewards_paid = 0;
+        staking_state.total_emitted = 0;
+        // Pools start on the flat `reward_rate_per_slot` until `set_emission_schedule` says otherwise
+        ctx.accounts.emission_schedule.set_inner(EmissionSchedule {
+            pool: staking_state.key(),
+            bump: ctx.bumps.emission_schedule,
+            ..Default::default()
+        });
+        staking_state.paused = false;
+        staking_state.transfers_restricted = false;
+
+        emit_cpi!(PoolInitialized {
+            pool: staking_state.key(),
+            pool_id,
+            admin: staking_state.admin,
+            staking_mint: staking_state.staking_mint,
+            reward_mint: staking_state.reward_mint,
+            receipt_mint: staking_state.receipt_mint,
+            reward_rate_per_slot,
+            timestamp: clock.unix_timestamp,
+        });
         Ok(())
     }
 
-    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+    pub fn open_position<'info>(ctx: Context<'_, '_, '_, 'info, OpenPosition<'info>>, amount: u64, lock_tier: u8) -> Result<()> {
+        require!(!is_paused(&ctx.accounts.staking_state, &ctx.accounts.governance_state), StakingError::Paused);
         require!(amount > 0, StakingError::InvalidAmount);
+        let tier = ctx.accounts.staking_state.lock_tier(&ctx.accounts.governance_state, lock_tier)?;
+        let fee = calculate_fee(amount, ctx.accounts.governance_state.staking_fee_bps)?;
+        let stake_amount = amount.checked_sub(fee).ok_or(StakingError::Overflow)?;
+        require!(stake_amount > 0, StakingError::InvalidAmount);
+
+        // Transfer the fee to treasury and the remainder to vault
+        let decimals = ctx.accounts.stake_mint.decimals;
+        if fee > 0 {
+            transfer_checked_with_hook(ctx.accounts.into_fee_transfer_ctx().with_remaining_accounts(ctx.remaining_accounts.to_vec()), fee, decimals)?;
+        }
+        let vault_before = ctx.accounts.vault_token_account.amount;
+        transfer_checked_with_hook(ctx.accounts.into_transfer_to_vault_ctx().with_remaining_accounts(ctx.remaining_accounts.to_vec()), stake_amount, decimals)?;
+
+        // Credit what the vault actually received, net of any Token-2022 transfer fee
+        let stake_amount = received_amount(&mut ctx.accounts.vault_token_account, vault_before)?;
+        require!(stake_amount > 0, StakingError::InvalidAmount);
+
+        // Accrue emissions first, so receipts are priced at the pool value, owed rewards included,
+        // as it stood before this deposit
+        let clock = Clock::get()?;
+        ctx.accounts.staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+        let receipts = calculate_receipt_amount(
+            stake_amount,
+            ctx.accounts.staking_state.pooled_value()?,
+            ctx.accounts.receipt_mint.supply,
+        )?;
+        require!(receipts > 0, StakingError::InvalidAmount);
+        let level_before = ctx.accounts.user_stake.level;
+
         let staking_state = &mut ctx.accounts.staking_state;
+        let position_counter = &mut ctx.accounts.position_counter;
         let user_stake = &mut ctx.accounts.user_stake;
 
-        // Transfer tokens to vault
-        token::transfer(ctx.accounts.into_transfer_to_vault_ctx(), amount)?;
+        position_counter.owner = ctx.accounts.user.key();
+        position_counter.bump = ctx.bumps.position_counter;
 
-        // Update staking state
-        staking_state.total_staked = staking_state.total_staked.checked_add(amount)
-            .ok_or(StakingError::Overflow)?;
-        
         user_stake.owner = ctx.accounts.user.key();
-        user_stake.amount = user_stake.amount.checked_add(amount)
+        user_stake.pool = staking_state.key();
+        user_stake.payer = ctx.accounts.user.key();
+        user_stake.position_id = position_counter.next_position_id;
+        user_stake.bump = ctx.bumps.user_stake;
+        staking_state.total_staking_fees = staking_state.total_staking_fees.checked_add(fee)
+            .ok_or(StakingError::Overflow)?;
+        user_stake.set_lock_tier(lock_tier, &tier, staking_state.lockup_period)?;
+        user_stake.add_stake(staking_state, &ctx.accounts.emission_schedule, stake_amount, &clock)?;
+        user_stake.receipt_amount = user_stake.receipt_amount.checked_add(receipts)
+            .ok_or(StakingError::Overflow)?;
+
+        position_counter.next_position_id = position_counter.next_position_id.checked_add(1)
+            .ok_or(StakingError::Overflow)?;
+        position_counter.open_positions = position_counter.open_positions.checked_add(1)
+            .ok_or(StakingError::Overflow)?;
+
+        let staking_mint = ctx.accounts.staking_state.staking_mint;
+        let pool_id = ctx.accounts.staking_state.pool_id.to_le_bytes();
+        let bump = [ctx.accounts.staking_state.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"staking-state", staking_mint.as_ref(), &pool_id, &bump]];
+        token_interface::mint_to(ctx.accounts.into_receipt_mint_ctx().with_signer(signer_seeds), receipts)?;
+
+        let user_stake = &ctx.accounts.user_stake;
+        emit_cpi!(Staked {
+            owner: ctx.accounts.user.key(),
+            pool: ctx.accounts.staking_state.key(),
+            position_id: user_stake.position_id,
+            amount: stake_amount,
+            fee,
+            receipts_minted: receipts,
+            new_stake_amount: user_stake.amount,
+            level_before,
+            level_after: user_stake.level,
+            lockup_end: user_stake.lockup_end,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn stake<'info>(ctx: Context<'_, '_, '_, 'info, Stake<'info>>, _position_id: u64, amount: u64, lock_tier: u8) -> Result<()> {
+        require!(!is_paused(&ctx.accounts.staking_state, &ctx.accounts.governance_state), StakingError::Paused);
+        require!(amount > 0, StakingError::InvalidAmount);
+        let tier = ctx.accounts.staking_state.lock_tier(&ctx.accounts.governance_state, lock_tier)?;
+        let fee = calculate_fee(amount, ctx.accounts.governance_state.staking_fee_bps)?;
+        let stake_amount = amount.checked_sub(fee).ok_or(StakingError::Overflow)?;
+        require!(stake_amount > 0, StakingError::InvalidAmount);
+
+        // Transfer the fee to treasury and the remainder to vault
+        let decimals = ctx.accounts.stake_mint.decimals;
+        if fee > 0 {
+            transfer_checked_with_hook(ctx.accounts.into_fee_transfer_ctx().with_remaining_accounts(ctx.remaining_accounts.to_vec()), fee, decimals)?;
+        }
+        let vault_before = ctx.accounts.vault_token_account.amount;
+        transfer_checked_with_hook(ctx.accounts.into_transfer_to_vault_ctx().with_remaining_accounts(ctx.remaining_accounts.to_vec()), stake_amount, decimals)?;
+
+        // Credit what the vault actually received, net of any Token-2022 transfer fee
+        let stake_amount = received_amount(&mut ctx.accounts.vault_token_account, vault_before)?;
+        require!(stake_amount > 0, StakingError::InvalidAmount);
+
+        // Accrue emissions first, so receipts are priced at the pool value, owed rewards included,
+        // as it stood before this deposit
+        let clock = Clock::get()?;
+        ctx.accounts.staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+        let receipts = calculate_receipt_amount(
+            stake_amount,
+            ctx.accounts.staking_state.pooled_value()?,
+            ctx.accounts.receipt_mint.supply,
+        )?;
+        require!(receipts > 0, StakingError::InvalidAmount);
+        let level_before = ctx.accounts.user_stake.level;
+
+        let staking_state = &mut ctx.accounts.staking_state;
+        let user_stake = &mut ctx.accounts.user_stake;
+        staking_state.total_staking_fees = staking_state.total_staking_fees.checked_add(fee)
+            .ok_or(StakingError::Overflow)?;
+        user_stake.set_lock_tier(lock_tier, &tier, staking_state.lockup_period)?;
+        user_stake.add_stake(staking_state, &ctx.accounts.emission_schedule, stake_amount, &clock)?;
+        user_stake.receipt_amount = user_stake.receipt_amount.checked_add(receipts)
             .ok_or(StakingError::Overflow)?;
-        user_stake.staked_at = Clock::get()?.unix_timestamp;
-        user_stake.lockup_end = user_stake.staked_at + staking_state.lockup_period;
-        user_stake.level = user_stake.calculate_level();
-        user_stake.status = StakingStatus::Active;
-        user_stake.last_claimed_at = 0;
+
+        let staking_mint = ctx.accounts.staking_state.staking_mint;
+        let pool_id = ctx.accounts.staking_state.pool_id.to_le_bytes();
+        let bump = [ctx.accounts.staking_state.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"staking-state", staking_mint.as_ref(), &pool_id, &bump]];
+        token_interface::mint_to(ctx.accounts.into_receipt_mint_ctx().with_signer(signer_seeds), receipts)?;
+
+        let user_stake = &ctx.accounts.user_stake;
+        emit_cpi!(Staked {
+            owner: ctx.accounts.user.key(),
+            pool: ctx.accounts.staking_state.key(),
+            position_id: user_stake.position_id,
+            amount: stake_amount,
+            fee,
+            receipts_minted: receipts,
+            new_stake_amount: user_stake.amount,
+            level_before,
+            level_after: user_stake.level,
+            lockup_end: user_stake.lockup_end,
+            timestamp: clock.unix_timestamp,
+        });
         Ok(())
     }
 
-    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+    pub fn unstake(ctx: Context<Unstake>, position_id: u64, amount: u64) -> Result<()> {
+        require!(!is_paused(&ctx.accounts.staking_state, &ctx.accounts.governance_state), StakingError::Paused);
         require!(amount > 0, StakingError::InvalidAmount);
         let staking_state = &mut ctx.accounts.staking_state;
         let user_stake = &mut ctx.accounts.user_stake;
+        require!(!user_stake.is_frozen(), StakingError::StakeFrozen);
         require!(user_stake.amount >= amount, StakingError::InsufficientStake);
         require!(user_stake.is_lockup_met(), StakingError::LockupPeriodNotMet);
+        let level_before = user_stake.level;
+
+        // Receipts worth the unbonding amount are redeemed up front, at the rate before it leaves
+        let clock = Clock::get()?;
+        staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+        let receipt_burn = staking_state.receipt_redemption(user_stake, amount, ctx.accounts.receipt_mint.supply)?;
+
+        // Unbonding tokens stop earning and leave total_staked immediately
+        user_stake.remove_stake(staking_state, &ctx.accounts.emission_schedule, amount, &clock)?;
+        let receipt_burn = user_stake.take_receipts(receipt_burn);
 
-        // Transfer tokens back to user
-        token::transfer(ctx.accounts.into_transfer_to_user_ctx(), amount)?;
+        let ticket = &mut ctx.accounts.unbonding_ticket;
+        ticket.owner = ctx.accounts.user.key();
+        ticket.position_id = position_id;
+        ticket.ticket_id = user_stake.next_ticket_id;
+        ticket.amount = amount;
+        ticket.fee = calculate_fee(amount, ctx.accounts.governance_state.unstaking_fee_bps)?;
+        ticket.status = StakingStatus::Unstaking;
+        ticket.created_at = clock.unix_timestamp;
+        ticket.unlock_at = clock.unix_timestamp.checked_add(staking_state.unbonding_period)
+            .ok_or(StakingError::Overflow)?;
+        ticket.bump = ctx.bumps.unbonding_ticket;
 
-        staking_state.total_staked = staking_state.total_staked.checked_sub(amount)
+        user_stake.next_ticket_id = user_stake.next_ticket_id.checked_add(1)
             .ok_or(StakingError::Overflow)?;
-        user_stake.amount = user_stake.amount.checked_sub(amount)
+        user_stake.unbonding_amount = user_stake.unbonding_amount.checked_add(amount)
+            .ok_or(StakingError::Overflow)?;
+        staking_state.total_unbonding = staking_state.total_unbonding.checked_add(amount)
             .ok_or(StakingError::Overflow)?;
-        
-        // Update level if amount changed significantly
-        user_stake.level = user_stake.calculate_level();
         
         // If unstaking everything, update status
         if user_stake.amount == 0 {
             user_stake.status = StakingStatus::Unstaking;
         }
+
+        if receipt_burn > 0 {
+            token_interface::burn(ctx.accounts.into_receipt_burn_ctx(), receipt_burn)?;
+        }
+
+        let ticket = &ctx.accounts.unbonding_ticket;
+        emit_cpi!(Unstaked {
+            owner: ctx.accounts.user.key(),
+            pool: ctx.accounts.staking_state.key(),
+            position_id,
+            ticket_id: Some(ticket.ticket_id),
+            amount,
+            fee: ticket.fee,
+            penalty: 0,
+            receipts_burned: receipt_burn,
+            remaining_amount: ctx.accounts.user_stake.amount,
+            level_before,
+            level_after: ctx.accounts.user_stake.level,
+            timestamp: clock.unix_timestamp,
+        });
         Ok(())
     }
 
-    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+    pub fn unstake_early<'info>(ctx: Context<'_, '_, '_, 'info, UnstakeEarly<'info>>, position_id: u64, amount: u64) -> Result<()> {
+        require!(!is_paused(&ctx.accounts.staking_state, &ctx.accounts.governance_state), StakingError::Paused);
+        require!(amount > 0, StakingError::InvalidAmount);
+        let governance_state = &ctx.accounts.governance_state;
+        let staking_state = &mut ctx.accounts.staking_state;
         let user_stake = &mut ctx.accounts.user_stake;
-        require!(user_stake.can_claim(), StakingError::ClaimCooldownNotMet);
+        require!(!user_stake.is_frozen(), StakingError::StakeFrozen);
+        require!(user_stake.amount >= amount, StakingError::InsufficientStake);
+        // Once the lockup is over the only exit is unstake and withdraw, through the unbonding period
+        require!(!user_stake.is_lockup_met(), StakingError::LockupEnded);
+        let level_before = user_stake.level;
 
-        let now = Clock::get()?.unix_timestamp;
-        let time_since_last_claim = now.checked_sub(user_stake.last_claimed_at)
+        // The penalty shrinks linearly as lockup_end approaches
+        let clock = Clock::get()?;
+        let remaining_lockup = user_stake.lockup_end.saturating_sub(clock.unix_timestamp);
+        let penalty = calculate_early_unstake_penalty(
+            amount,
+            governance_state.early_unstake_penalty_bps,
+            remaining_lockup,
+            user_stake.lock_duration,
+        )?;
+        let after_penalty = amount.checked_sub(penalty).ok_or(StakingError::Overflow)?;
+        let fee = calculate_fee(after_penalty, governance_state.unstaking_fee_bps)?;
+        let user_amount = after_penalty.checked_sub(fee).ok_or(StakingError::Overflow)?;
+
+        // Receipts are redeemed for the full amount leaving the position, penalty included
+        staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+        let receipt_burn = staking_state.receipt_redemption(user_stake, amount, ctx.accounts.receipt_mint.supply)?;
+        user_stake.remove_stake(staking_state, &ctx.accounts.emission_schedule, amount, &clock)?;
+        let receipt_burn = user_stake.take_receipts(receipt_burn);
+        if user_stake.amount == 0 {
+            user_stake.status = StakingStatus::Unstaking;
+        }
+
+        staking_state.total_unstaking_fees = staking_state.total_unstaking_fees.checked_add(fee)
+            .ok_or(StakingError::Overflow)?;
+        staking_state.total_penalties = staking_state.total_penalties.checked_add(penalty)
             .ok_or(StakingError::Overflow)?;
 
-        // Calculate reward based on amount, time, and multiplier
-        let multiplier = user_stake.get_multiplier(&ctx.accounts.staking_state);
-        let base_reward = user_stake.amount.checked_mul(time_since_last_claim)
+        // With nobody left to redistribute to, the penalty goes to treasury instead
+        let mut destination = governance_state.penalty_destination;
+        if destination == PenaltyDestination::Redistribute && staking_state.total_weighted_stake == 0 {
+            destination = PenaltyDestination::Treasury;
+        }
+        if destination == PenaltyDestination::Redistribute && penalty > 0 {
+            require!(
+                ctx.accounts.reward_vault.mint == ctx.accounts.vault_token_account.mint,
+                StakingError::MintMismatch
+            );
+        }
+
+        let staking_mint = ctx.accounts.staking_state.staking_mint;
+        let pool_id = ctx.accounts.staking_state.pool_id.to_le_bytes();
+        let bump = [ctx.accounts.staking_state.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"staking-state", staking_mint.as_ref(), &pool_id, &bump]];
+        let decimals = ctx.accounts.stake_mint.decimals;
+        let mut treasury_amount = fee;
+        if penalty > 0 {
+            match destination {
+                PenaltyDestination::Burn => token_interface::burn(ctx.accounts.into_burn_ctx().with_signer(signer_seeds), penalty)?,
+                PenaltyDestination::Treasury => {
+                    treasury_amount = treasury_amount.checked_add(penalty).ok_or(StakingError::Overflow)?;
+                }
+                PenaltyDestination::Redistribute => {
+                    // Only what reaches the reward vault after any transfer fee can be paid out again
+                    let vault_before = ctx.accounts.reward_vault.amount;
+                    transfer_checked_with_hook(ctx.accounts.into_transfer_to_reward_vault_ctx().with_signer(signer_seeds).with_remaining_accounts(ctx.remaining_accounts.to_vec()), penalty, decimals)?;
+                    let redistributed = received_amount(&mut ctx.accounts.reward_vault, vault_before)?;
+                    ctx.accounts.staking_state.distribute_rewards(redistributed)?;
+                }
+            }
+        }
+        if treasury_amount > 0 {
+            transfer_checked_with_hook(ctx.accounts.into_fee_transfer_ctx().with_signer(signer_seeds).with_remaining_accounts(ctx.remaining_accounts.to_vec()), treasury_amount, decimals)?;
+        }
+        transfer_checked_with_hook(ctx.accounts.into_transfer_to_user_ctx().with_signer(signer_seeds).with_remaining_accounts(ctx.remaining_accounts.to_vec()), user_amount, decimals)?;
+        if receipt_burn > 0 {
+            token_interface::burn(ctx.accounts.into_receipt_burn_ctx(), receipt_burn)?;
+        }
+
+        emit_cpi!(Unstaked {
+            owner: ctx.accounts.user.key(),
+            pool: ctx.accounts.staking_state.key(),
+            position_id,
+            ticket_id: None,
+            amount,
+            fee,
+            penalty,
+            receipts_burned: receipt_burn,
+            remaining_amount: ctx.accounts.user_stake.amount,
+            level_before,
+            level_after: ctx.accounts.user_stake.level,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn withdraw<'info>(ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>, position_id: u64, ticket_id: u64) -> Result<()> {
+        let amount = ctx.accounts.unbonding_ticket.amount;
+        let fee = ctx.accounts.unbonding_ticket.fee;
+        // While paused the unbonding wait is waived so users can exit
+        require!(
+            ctx.accounts.unbonding_ticket.is_unlocked()
+                || is_paused(&ctx.accounts.staking_state, &ctx.accounts.governance_state),
+            StakingError::UnbondingPeriodNotMet
+        );
+        require!(!ctx.accounts.user_stake.is_frozen(), StakingError::StakeFrozen);
+
+        // Transfer the fee to treasury and the remainder back to user
+        let staking_mint = ctx.accounts.staking_state.staking_mint;
+        let pool_id = ctx.accounts.staking_state.pool_id.to_le_bytes();
+        let bump = [ctx.accounts.staking_state.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"staking-state", staking_mint.as_ref(), &pool_id, &bump]];
+        let decimals = ctx.accounts.stake_mint.decimals;
+        if fee > 0 {
+            transfer_checked_with_hook(ctx.accounts.into_fee_transfer_ctx().with_signer(signer_seeds).with_remaining_accounts(ctx.remaining_accounts.to_vec()), fee, decimals)?;
+        }
+        let user_amount = amount.checked_sub(fee).ok_or(StakingError::Overflow)?;
+        transfer_checked_with_hook(ctx.accounts.into_transfer_to_user_ctx().with_signer(signer_seeds).with_remaining_accounts(ctx.remaining_accounts.to_vec()), user_amount, decimals)?;
+
+        let staking_state = &mut ctx.accounts.staking_state;
+        let user_stake = &mut ctx.accounts.user_stake;
+        staking_state.total_unstaking_fees = staking_state.total_unstaking_fees.checked_add(fee)
             .ok_or(StakingError::Overflow)?;
-        let reward = base_reward.checked_mul(multiplier)
-            .ok_or(StakingError::Overflow)?
-            .checked_div(1000)
-            .ok_or(StakingError::Overflow)?
-            .checked_div(365 * 24 * 60 * 60) // Annual rate
+        user_stake.unbonding_amount = user_stake.unbonding_amount.checked_sub(amount)
+            .ok_or(StakingError::Overflow)?;
+        staking_state.total_unbonding = staking_state.total_unbonding.checked_sub(amount)
             .ok_or(StakingError::Overflow)?;
 
-        require!(reward > 0, StakingError::InvalidAmount);
+        emit_cpi!(Withdrawn {
+            owner: ctx.accounts.user.key(),
+            pool: staking_state.key(),
+            position_id,
+            ticket_id,
+            amount,
+            fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn cancel_unbonding(ctx: Context<CancelUnbonding>, position_id: u64, ticket_id: u64) -> Result<()> {
+        require!(!is_paused(&ctx.accounts.staking_state, &ctx.accounts.governance_state), StakingError::Paused);
+        let amount = ctx.accounts.unbonding_ticket.amount;
+
+        // Restaked tokens get fresh receipts at the current exchange rate
+        let clock = Clock::get()?;
+        ctx.accounts.staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+        let receipts = calculate_receipt_amount(
+            amount,
+            ctx.accounts.staking_state.pooled_value()?,
+            ctx.accounts.receipt_mint.supply,
+        )?;
+
+        let staking_state = &mut ctx.accounts.staking_state;
+        let user_stake = &mut ctx.accounts.user_stake;
+
+        user_stake.unbonding_amount = user_stake.unbonding_amount.checked_sub(amount)
+            .ok_or(StakingError::Overflow)?;
+        staking_state.total_unbonding = staking_state.total_unbonding.checked_sub(amount)
+            .ok_or(StakingError::Overflow)?;
+
+        // Restaked tokens are locked again under the position's tier
+        user_stake.add_stake(staking_state, &ctx.accounts.emission_schedule, amount, &clock)?;
+        user_stake.receipt_amount = user_stake.receipt_amount.checked_add(receipts)
+            .ok_or(StakingError::Overflow)?;
+
+        if receipts > 0 {
+            let staking_mint = ctx.accounts.staking_state.staking_mint;
+            let pool_id = ctx.accounts.staking_state.pool_id.to_le_bytes();
+            let bump = [ctx.accounts.staking_state.bump];
+            let signer_seeds: &[&[&[u8]]] = &[&[b"staking-state", staking_mint.as_ref(), &pool_id, &bump]];
+            token_interface::mint_to(ctx.accounts.into_receipt_mint_ctx().with_signer(signer_seeds), receipts)?;
+        }
 
-        token::transfer(ctx.accounts.into_reward_transfer_ctx(), reward)?;
-        user_stake.last_claimed_at = now;
+        emit_cpi!(UnbondingCancelled {
+            owner: ctx.accounts.user.key(),
+            pool: ctx.accounts.staking_state.key(),
+            position_id,
+            ticket_id,
+            amount,
+            receipts_minted: receipts,
+            new_stake_amount: ctx.accounts.user_stake.amount,
+            timestamp: clock.unix_timestamp,
+        });
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(init, payer = authority, space = 8 + StakingState::SIZE, seeds = [b"staking-state"], bump)]
-    pub staking_state: Account<'info, StakingState>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+    /// Burn receipts left on a position with no stake, e.g. after a full slash, so it can be closed
+    pub fn burn_receipts(ctx: Context<BurnReceipts>, position_id: u64) -> Result<()> {
+        let amount = ctx.accounts.user_stake.receipt_amount;
+        require!(amount > 0, StakingError::InvalidAmount);
+        require!(ctx.accounts.user_stake.amount == 0, StakingError::PositionNotEmpty);
 
-#[derive(Accounts)]
-pub struct Stake<'info> {
-    #[account(mut)]
-    pub staking_state: Account<'info, StakingState>,
-    #[account(init_if_needed, payer = user, space = 8 + UserStake::SIZE, seeds = [b"user-stake", user.key().as_ref()], bump)]
-    pub user_stake: Account<'info, UserStake>,
-    #[account(mut)]
-    pub user: Signer<'info>,
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub vault_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
+        ctx.accounts.user_stake.receipt_amount = 0;
+        token_interface::burn(ctx.accounts.into_receipt_burn_ctx(), amount)?;
 
-#[derive(Accounts)]
-pub struct Unstake<'info> {
-    #[account(mut)]
-    pub staking_state: Account<'info, StakingState>,
-    #[account(mut, seeds = [b"user-stake", user.key().as_ref()], bump)]
-    pub user_stake: Account<'info, UserStake>,
-    #[account(mut)]
-    pub user: Signer<'info>,
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub vault_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-}
+        emit_cpi!(ReceiptsBurned {
+            owner: ctx.accounts.user.key(),
+            pool: ctx.accounts.staking_state.key(),
+            position_id,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct Claim<'info> {
-    #[account(mut, seeds = [b"user-stake", user.key().as_ref()], bump)]
-    pub user_stake: Account<'info, UserStake>,
-    #[account(mut)]
-    pub user: Signer<'info>,
-    #[account(mut)]
-    pub reward_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-}
+    pub fn close_stake(ctx: Context<CloseStake>, position_id: u64) -> Result<()> {
+        require!(ctx.accounts.user_stake.is_empty(), StakingError::PositionNotEmpty);
 
-impl<'info> Stake<'info> {
-    fn into_transfer_to_vault_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self.user_token_account.to_account_info(),
-            to: self.vault_token_account.to_account_info(),
-            authority: self.user.to_account_info(),
+        let position_counter = &mut ctx.accounts.position_counter;
+        position_counter.open_positions = position_counter.open_positions.checked_sub(1)
+            .ok_or(StakingError::Overflow)?;
+
+        emit_cpi!(StakeClosed {
+            owner: ctx.accounts.user.key(),
+            pool: ctx.accounts.staking_state.key(),
+            position_id,
+            rent_receiver: ctx.accounts.user.key(),
+            swept: false,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Admin cleanup of an abandoned empty position, one left untouched for `SWEEP_INACTIVITY_PERIOD`.
+    /// Rent goes back to whoever paid for it.
+    pub fn sweep_stake(ctx: Context<SweepStake>, position_id: u64) -> Result<()> {
+        require!(ctx.accounts.user_stake.is_empty(), StakingError::PositionNotEmpty);
+        let now = Clock::get()?.unix_timestamp;
+        require!(ctx.accounts.user_stake.is_abandoned(now), StakingError::PositionNotAbandoned);
+
+        let position_counter = &mut ctx.accounts.position_counter;
+        position_counter.open_positions = position_counter.open_positions.checked_sub(1)
+            .ok_or(StakingError::Overflow)?;
+
+        emit_cpi!(StakeClosed {
+            owner: ctx.accounts.user_stake.owner,
+            pool: ctx.accounts.staking_state.key(),
+            position_id,
+            rent_receiver: ctx.accounts.payer.key(),
+            swept: true,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Move a position to another wallet. The position is re-created under the new owner's next
+    /// position id with its amount, lockup, level, loyalty clock and unclaimed rewards intact.
+    pub fn transfer_position(ctx: Context<TransferPosition>, position_id: u64) -> Result<()> {
+        require!(!is_paused(&ctx.accounts.staking_state, &ctx.accounts.governance_state), StakingError::Paused);
+        require!(!ctx.accounts.staking_state.transfers_restricted, StakingError::TransfersRestricted);
+        let position = (*ctx.accounts.user_stake).clone();
+        require!(!position.is_frozen(), StakingError::StakeFrozen);
+        // Unbonding tickets are seeded by the old position and would be stranded
+        require!(position.unbonding_amount == 0, StakingError::PositionNotEmpty);
+
+        let new_owner = ctx.accounts.new_owner.key();
+        let new_position_counter = &mut ctx.accounts.new_position_counter;
+        new_position_counter.owner = new_owner;
+        new_position_counter.bump = ctx.bumps.new_position_counter;
+        let new_position_id = new_position_counter.next_position_id;
+
+        ctx.accounts.new_user_stake.set_inner(UserStake {
+            owner: new_owner,
+            position_id: new_position_id,
+            payer: ctx.accounts.user.key(),
+            bump: ctx.bumps.new_user_stake,
+            ..position
+        });
+
+        new_position_counter.next_position_id = new_position_id.checked_add(1)
+            .ok_or(StakingError::Overflow)?;
+        new_position_counter.open_positions = new_position_counter.open_positions.checked_add(1)
+            .ok_or(StakingError::Overflow)?;
+        let position_counter = &mut ctx.accounts.position_counter;
+        position_counter.open_positions = position_counter.open_positions.checked_sub(1)
+            .ok_or(StakingError::Overflow)?;
+
+        // The position's receipts follow it so the new owner can unstake
+        if position.receipt_amount > 0 {
+            let decimals = ctx.accounts.receipt_mint.decimals;
+            token_interface::transfer_checked(ctx.accounts.into_receipt_transfer_ctx(), position.receipt_amount, decimals)?;
+        }
+
+        emit_cpi!(PositionTransferred {
+            pool: ctx.accounts.staking_state.key(),
+            from: ctx.accounts.user.key(),
+            from_position_id: position_id,
+            to: new_owner,
+            to_position_id: new_position_id,
+            amount: position.amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Claim the main reward and every partner stream whose accounts are passed in remaining accounts
+    /// as `(stream vault, user token account, stream mint, stream token program)` groups of four,
+    /// followed by any transfer-hook accounts the mints need
+    pub fn claim<'info>(ctx: Context<'_, '_, 'info, 'info, Claim<'info>>, position_id: u64) -> Result<()> {
+        require!(!is_paused(&ctx.accounts.staking_state, &ctx.accounts.governance_state), StakingError::Paused);
+        let staking_state = &mut ctx.accounts.staking_state;
+        let user_stake = &mut ctx.accounts.user_stake;
+        require!(!user_stake.is_frozen(), StakingError::StakeFrozen);
+        require!(user_stake.can_claim(staking_state.claim_cooldown), StakingError::ClaimCooldownNotMet);
+
+        let level_before = user_stake.level;
+        let clock = Clock::get()?;
+        staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+        user_stake.settle_rewards(staking_state)?;
+        // Rewards so far were earned at the old level; re-evaluate it for what comes next
+        user_stake.refresh_level(staking_state, &clock)?;
+
+        let fee = calculate_fee(user_stake.pending_rewards, ctx.accounts.governance_state.claim_fee_bps)?;
+        let reward = user_stake.pending_rewards.checked_sub(fee)
+            .ok_or(StakingError::Overflow)?;
+
+        // Where owed rewards back stBONSAI, paying them out redeems receipts worth the same
+        let receipt_burn = if staking_state.rewards_back_receipts() {
+            staking_state.receipt_redemption(user_stake, user_stake.pending_rewards, ctx.accounts.receipt_mint.supply)?
+        } else {
+            0
         };
-        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+
+        staking_state.pay_out_rewards(user_stake.pending_rewards)?;
+        user_stake.pending_rewards = 0;
+        let receipt_burn = user_stake.take_receipts(receipt_burn);
+        user_stake.last_claimed_at = clock.unix_timestamp;
+        user_stake.last_updated_slot = clock.slot;
+        staking_state.total_claim_fees = staking_state.total_claim_fees.checked_add(fee)
+            .ok_or(StakingError::Overflow)?;
+
+        let staking_mint = ctx.accounts.staking_state.staking_mint;
+        let pool_id = ctx.accounts.staking_state.pool_id.to_le_bytes();
+        let bump = [ctx.accounts.staking_state.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"staking-state", staking_mint.as_ref(), &pool_id, &bump]];
+        let decimals = ctx.accounts.reward_mint.decimals;
+        if fee > 0 {
+            transfer_checked_with_hook(ctx.accounts.into_fee_transfer_ctx().with_signer(signer_seeds).with_remaining_accounts(ctx.remaining_accounts.to_vec()), fee, decimals)?;
+        }
+        if reward > 0 {
+            transfer_checked_with_hook(ctx.accounts.into_reward_transfer_ctx().with_signer(signer_seeds).with_remaining_accounts(ctx.remaining_accounts.to_vec()), reward, decimals)?;
+        }
+        if receipt_burn > 0 {
+            token_interface::burn(ctx.accounts.into_receipt_burn_ctx(), receipt_burn)?;
+        }
+
+        // Partner streams carry no claim fee; streams left out of remaining accounts stay pending.
+        // Stream accounts come first, anything after them is for transfer hooks.
+        let remaining_accounts = ctx.remaining_accounts;
+        let mut stream_paid = false;
+        for accounts in remaining_accounts.chunks_exact(4) {
+            let (stream_vault, user_stream_account, stream_mint, stream_token_program) =
+                (&accounts[0], &accounts[1], &accounts[2], &accounts[3]);
+            let index = match ctx.accounts.staking_state.find_reward_stream(stream_vault.key) {
+                Some(index) => index,
+                None => break,
+            };
+            require_keys_eq!(stream_mint.key(), ctx.accounts.staking_state.reward_streams[index].mint, StakingError::InvalidRewardStream);
+            require_keys_eq!(stream_token_program.key(), *stream_mint.owner, StakingError::InvalidRewardStream);
+
+            let amount = ctx.accounts.user_stake.stream_pending_rewards[index];
+            if amount == 0 {
+                continue;
+            }
+            ctx.accounts.user_stake.stream_pending_rewards[index] = 0;
+
+            let decimals = InterfaceAccount::<Mint>::try_from(stream_mint)?.decimals;
+            let cpi_accounts = TransferChecked {
+                from: stream_vault.clone(),
+                mint: stream_mint.clone(),
+                to: user_stream_account.clone(),
+                authority: ctx.accounts.staking_state.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(stream_token_program.clone(), cpi_accounts);
+            transfer_checked_with_hook(cpi_ctx.with_signer(signer_seeds).with_remaining_accounts(remaining_accounts.to_vec()), amount, decimals)?;
+            stream_paid = true;
+        }
+        require!(reward > 0 || stream_paid, StakingError::InvalidAmount);
+
+        emit_cpi!(RewardsClaimed {
+            owner: ctx.accounts.user.key(),
+            pool: ctx.accounts.staking_state.key(),
+            position_id,
+            amount: reward,
+            fee,
+            receipts_burned: receipt_burn,
+            level_before,
+            level_after: ctx.accounts.user_stake.level,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
     }
-}
 
-impl<'info> Unstake<'info> {
-    fn into_transfer_to_user_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self.vault_token_account.to_account_info(),
-            to: self.user_token_account.to_account_info(),
-            authority: self.staking_state.to_account_info(), // will be signed by PDA in future
+    /// Deposit `amount` of the reward mint into the reward vault. Only funded rewards are emitted.
+    pub fn fund_rewards<'info>(ctx: Context<'_, '_, '_, 'info, FundRewards<'info>>, amount: u64) -> Result<()> {
+        require!(amount > 0, StakingError::InvalidAmount);
+
+        // Accrue first so slots spent underfunded are not paid retroactively
+        let clock = Clock::get()?;
+        ctx.accounts.staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+
+        let decimals = ctx.accounts.reward_mint.decimals;
+        let vault_before = ctx.accounts.reward_vault.amount;
+        transfer_checked_with_hook(ctx.accounts.into_fund_transfer_ctx().with_remaining_accounts(ctx.remaining_accounts.to_vec()), amount, decimals)?;
+        let amount = received_amount(&mut ctx.accounts.reward_vault, vault_before)?;
+
+        let staking_state = &mut ctx.accounts.staking_state;
+        staking_state.fund_rewards(amount)?;
+
+        emit_cpi!(RewardsFunded {
+            pool: staking_state.key(),
+            funder: ctx.accounts.funder.key(),
+            amount,
+            total_funded: staking_state.total_funded,
+            total_owed: staking_state.total_rewards_owed,
+            runway_slots: staking_state.reward_runway(&ctx.accounts.emission_schedule, clock.slot)?,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Add a partner reward stream paying `rate_per_slot` of `stream_mint` from `start_slot` until
+    /// `end_slot`. Its vault is funded with plain token transfers.
+    pub fn add_reward_stream(ctx: Context<AddRewardStream>, rate_per_slot: u64, start_slot: u64, end_slot: u64) -> Result<()> {
+        validate_reward_rate(rate_per_slot)?;
+        let clock = Clock::get()?;
+        require!(start_slot >= clock.slot && end_slot > start_slot, StakingError::InvalidRewardStream);
+
+        let staking_state = &mut ctx.accounts.staking_state;
+        let index = staking_state.reward_stream_count as usize;
+        require!(index < MAX_REWARD_STREAMS, StakingError::TooManyRewardStreams);
+
+        // Bring the existing accumulators up to date before the new stream joins
+        staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+        staking_state.reward_streams[index] = RewardStream {
+            mint: ctx.accounts.stream_mint.key(),
+            vault: ctx.accounts.stream_vault.key(),
+            rate_per_slot,
+            start_slot,
+            end_slot,
+            acc_reward_per_share: 0,
         };
-        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+        staking_state.reward_stream_count += 1;
+
+        emit_cpi!(RewardStreamAdded {
+            pool: staking_state.key(),
+            index: index as u8,
+            mint: ctx.accounts.stream_mint.key(),
+            vault: ctx.accounts.stream_vault.key(),
+            rate_per_slot,
+            start_slot,
+            end_slot,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
     }
-}
 
-impl<'info> Claim<'info> {
-    fn into_reward_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self.reward_vault.to_account_info(),
-            to: self.user_token_account.to_account_info(),
-            authority: self.reward_vault.to_account_info(), // change to reward authority PDA
+    /// Permissionless crank that re-applies a position's level and loyalty boost, which otherwise only
+    /// move when the owner touches the position
+    pub fn refresh_position(ctx: Context<RefreshPosition>, position_id: u64) -> Result<()> {
+        require!(!is_paused(&ctx.accounts.staking_state, &ctx.accounts.governance_state), StakingError::Paused);
+        let staking_state = &mut ctx.accounts.staking_state;
+        let user_stake = &mut ctx.accounts.user_stake;
+        require!(!user_stake.is_frozen(), StakingError::StakeFrozen);
+        require!(user_stake.amount > 0, StakingError::InsufficientStake);
+        let level_before = user_stake.level;
+
+        // Rewards so far were earned at the old weight
+        let clock = Clock::get()?;
+        staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+        user_stake.settle_rewards(staking_state)?;
+        user_stake.refresh_level(staking_state, &clock)?;
+        user_stake.last_updated_slot = clock.slot;
+
+        emit_cpi!(PositionRefreshed {
+            owner: user_stake.owner,
+            pool: staking_state.key(),
+            position_id,
+            level_before,
+            level_after: user_stake.level,
+            weighted_amount: user_stake.weighted_amount,
+            cranker: ctx.accounts.cranker.key(),
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Permissionless crank copying level tiers, lockup, cooldown and loyalty boost from governance
+    pub fn sync_from_governance(ctx: Context<SyncFromGovernance>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let staking_state = &mut ctx.accounts.staking_state;
+        staking_state.sync_from_governance(&ctx.accounts.governance_state, now)?;
+
+        emit_cpi!(GovernanceSynced {
+            pool: staking_state.key(),
+            level_tiers: staking_state.active_level_tiers().to_vec(),
+            lockup_period: staking_state.lockup_period,
+            claim_cooldown: staking_state.claim_cooldown,
+            loyalty_bps_per_period: staking_state.loyalty_bps_per_period,
+            loyalty_period: staking_state.loyalty_period,
+            max_loyalty_bps: staking_state.max_loyalty_bps,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Give the pool its own level tiers, lock tiers or lockup period instead of governance's.
+    /// `None` hands a setting back to governance, which is re-synced straight away.
+    pub fn set_pool_terms(
+        ctx: Context<SetPoolTerms>,
+        level_tiers: Option<Vec<LevelTier>>,
+        lock_tiers: Option<Vec<LockTier>>,
+        lockup_period: Option<i64>,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let staking_state = &mut ctx.accounts.staking_state;
+        staking_state.custom_level_tiers = false;
+        staking_state.custom_lockup_period = false;
+        staking_state.sync_from_governance(&ctx.accounts.governance_state, now)?;
+
+        if let Some(tiers) = &level_tiers {
+            staking_state.set_level_tiers(tiers)?;
+            staking_state.custom_level_tiers = true;
+        }
+        match &lock_tiers {
+            Some(tiers) => staking_state.set_lock_tiers(tiers)?,
+            None => staking_state.custom_lock_tiers = false,
+        }
+        if let Some(lockup_period) = lockup_period {
+            validate_lockup_period(lockup_period)?;
+            staking_state.lockup_period = lockup_period;
+            staking_state.custom_lockup_period = true;
+        }
+
+        emit_cpi!(PoolTermsUpdated {
+            pool: staking_state.key(),
+            admin: ctx.accounts.admin.key(),
+            level_tiers,
+            lock_tiers,
+            lockup_period,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Claim cooldown and the loyalty boost always come from governance; level tiers and lockup do
+    /// unless overridden with `set_pool_terms`
+    pub fn update_config(ctx: Context<UpdateConfig>, reward_rate_per_slot: u64, level_window: i64) -> Result<()> {
+        validate_reward_rate(reward_rate_per_slot)?;
+        validate_level_window(level_window)?;
+
+        // Accrue up to now at the old rate before switching
+        let staking_state = &mut ctx.accounts.staking_state;
+        staking_state.update_rewards(&ctx.accounts.emission_schedule, Clock::get()?.slot)?;
+        staking_state.reward_rate_per_slot = reward_rate_per_slot;
+        staking_state.level_window = level_window;
+
+        emit_cpi!(ConfigUpdated {
+            pool: staking_state.key(),
+            admin: ctx.accounts.admin.key(),
+            reward_rate_per_slot,
+            level_window,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Replace the main reward's emission schedule. Rewards are accrued under the old schedule first.
+    pub fn set_emission_schedule(
+        ctx: Context<SetEmissionSchedule>,
+        curve: EmissionCurve,
+        segments: Vec<RateSegment>,
+        decay: DecayCurve,
+        emission_cap: u64,
+    ) -> Result<()> {
+        let schedule = EmissionSchedule::new(curve, &segments, decay, emission_cap)?;
+
+        let staking_state = &mut ctx.accounts.staking_state;
+        let clock = Clock::get()?;
+        staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+        require!(
+            emission_cap == 0 || emission_cap >= staking_state.total_emitted,
+            StakingError::InvalidEmissionSchedule
+        );
+        ctx.accounts.emission_schedule.set_inner(EmissionSchedule {
+            pool: staking_state.key(),
+            bump: ctx.accounts.emission_schedule.bump,
+            ..schedule
+        });
+
+        emit_cpi!(EmissionScheduleUpdated {
+            pool: staking_state.key(),
+            admin: ctx.accounts.admin.key(),
+            curve,
+            segments,
+            decay,
+            emission_cap,
+            total_emitted: staking_state.total_emitted,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Nominate a new admin. Passing the default pubkey cancels a pending nomination.
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.staking_state.pending_admin = new_admin;
+
+        emit_cpi!(AdminProposed {
+            pool: ctx.accounts.staking_state.key(),
+            admin: ctx.accounts.admin.key(),
+            pending_admin: new_admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let staking_state = &mut ctx.accounts.staking_state;
+        let previous_admin = staking_state.admin;
+        staking_state.admin = ctx.accounts.pending_admin.key();
+        staking_state.pending_admin = Pubkey::default();
+
+        emit_cpi!(AdminTransferred {
+            pool: staking_state.key(),
+            previous_admin,
+            new_admin: staking_state.admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.staking_state.paused = paused;
+        emit_cpi!(PauseUpdated {
+            pool: ctx.accounts.staking_state.key(),
+            admin: ctx.accounts.admin.key(),
+            paused,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn set_transfers_restricted(ctx: Context<SetTransfersRestricted>, restricted: bool) -> Result<()> {
+        ctx.accounts.staking_state.transfers_restricted = restricted;
+
+        emit_cpi!(TransfersRestrictedUpdated {
+            pool: ctx.accounts.staking_state.key(),
+            admin: ctx.accounts.admin.key(),
+            restricted,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Return a position's principal while the program is paused, ignoring its lockup.
+    /// Unsettled rewards are forfeited and no fees are charged.
+    pub fn emergency_withdraw<'info>(ctx: Context<'_, '_, '_, 'info, EmergencyWithdraw<'info>>, position_id: u64) -> Result<()> {
+        require!(is_paused(&ctx.accounts.staking_state, &ctx.accounts.governance_state), StakingError::NotPaused);
+        require!(!ctx.accounts.user_stake.is_frozen(), StakingError::StakeFrozen);
+
+        // Emit up to now at the current weights before this position's weight leaves the pool
+        let clock = Clock::get()?;
+        ctx.accounts.staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+
+        let receipt_burn = ctx.accounts.user_stake.receipt_amount;
+        let amount = ctx.accounts.user_stake.emergency_exit(&mut ctx.accounts.staking_state)?;
+        require!(amount > 0, StakingError::InsufficientStake);
+        ctx.accounts.user_stake.receipt_amount = 0;
+        ctx.accounts.user_stake.status = StakingStatus::Unstaking;
+        ctx.accounts.user_stake.last_updated_slot = clock.slot;
+
+        let staking_mint = ctx.accounts.staking_state.staking_mint;
+        let pool_id = ctx.accounts.staking_state.pool_id.to_le_bytes();
+        let bump = [ctx.accounts.staking_state.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"staking-state", staking_mint.as_ref(), &pool_id, &bump]];
+        let decimals = ctx.accounts.stake_mint.decimals;
+        transfer_checked_with_hook(ctx.accounts.into_transfer_to_user_ctx().with_signer(signer_seeds).with_remaining_accounts(ctx.remaining_accounts.to_vec()), amount, decimals)?;
+        if receipt_burn > 0 {
+            token_interface::burn(ctx.accounts.into_receipt_burn_ctx(), receipt_burn)?;
+        }
+
+        emit_cpi!(EmergencyWithdrawn {
+            owner: ctx.accounts.user.key(),
+            pool: ctx.accounts.staking_state.key(),
+            position_id,
+            amount,
+            receipts_burned: receipt_burn,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn set_slashing_authority(ctx: Context<SetSlashingAuthority>, slashing_authority: Pubkey) -> Result<()> {
+        let previous_authority = ctx.accounts.staking_state.slashing_authority;
+        ctx.accounts.staking_state.slashing_authority = slashing_authority;
+
+        emit_cpi!(SlashingAuthorityUpdated {
+            pool: ctx.accounts.staking_state.key(),
+            admin: ctx.accounts.admin.key(),
+            previous_authority,
+            new_authority: slashing_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn slash<'info>(
+        ctx: Context<'_, '_, '_, 'info, Slash<'info>>,
+        position_id: u64,
+        slash_bps: u64,
+        reason_code: u16,
+        freeze: bool,
+    ) -> Result<()> {
+        require!(slash_bps > 0 && slash_bps <= 10000, StakingError::InvalidSlashAmount);
+        let staking_state = &mut ctx.accounts.staking_state;
+        let user_stake = &mut ctx.accounts.user_stake;
+
+        let slashed = (user_stake.amount as u128)
+            .checked_mul(slash_bps as u128)
+            .ok_or(StakingError::Overflow)?
+            .checked_div(10000)
+            .ok_or(StakingError::Overflow)? as u64;
+
+        let clock = Clock::get()?;
+        // Receipts stay with the position: they are burned with whatever stake is left, or through
+        // burn_receipts if nothing is, and the position cannot be closed while it still holds any
+        user_stake.remove_stake(staking_state, &ctx.accounts.emission_schedule, slashed, &clock)?;
+        user_stake.total_slashed = user_stake.total_slashed.checked_add(slashed)
+            .ok_or(StakingError::Overflow)?;
+        staking_state.total_slashed = staking_state.total_slashed.checked_add(slashed)
+            .ok_or(StakingError::Overflow)?;
+        if freeze {
+            user_stake.status = StakingStatus::Locked;
+        }
+
+        emit_cpi!(StakeSlashed {
+            owner: user_stake.owner,
+            pool: staking_state.key(),
+            position_id,
+            ticket_id: None,
+            amount: slashed,
+            slash_bps,
+            reason_code,
+            frozen: user_stake.is_frozen(),
+            authority: ctx.accounts.slashing_authority.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        // Move the slashed stake to treasury
+        if slashed > 0 {
+            let staking_mint = ctx.accounts.staking_state.staking_mint;
+            let pool_id = ctx.accounts.staking_state.pool_id.to_le_bytes();
+            let bump = [ctx.accounts.staking_state.bump];
+            let signer_seeds: &[&[&[u8]]] = &[&[b"staking-state", staking_mint.as_ref(), &pool_id, &bump]];
+            let decimals = ctx.accounts.stake_mint.decimals;
+            transfer_checked_with_hook(ctx.accounts.into_slash_transfer_ctx().with_signer(signer_seeds).with_remaining_accounts(ctx.remaining_accounts.to_vec()), slashed, decimals)?;
+        }
+        Ok(())
+    }
+
+    /// Slash an unbonding ticket, so stake cannot escape a pending slash by being unstaked first
+    pub fn slash_unbonding<'info>(
+        ctx: Context<'_, '_, '_, 'info, SlashUnbonding<'info>>,
+        position_id: u64,
+        ticket_id: u64,
+        slash_bps: u64,
+        reason_code: u16,
+        freeze: bool,
+    ) -> Result<()> {
+        require!(slash_bps > 0 && slash_bps <= 10000, StakingError::InvalidSlashAmount);
+        let staking_state = &mut ctx.accounts.staking_state;
+        let user_stake = &mut ctx.accounts.user_stake;
+        let ticket = &mut ctx.accounts.unbonding_ticket;
+        require!(ticket.amount > 0, StakingError::InsufficientStake);
+
+        let slashed = (ticket.amount as u128)
+            .checked_mul(slash_bps as u128)
+            .ok_or(StakingError::Overflow)?
+            .checked_div(10000)
+            .ok_or(StakingError::Overflow)? as u64;
+        let remaining = ticket.amount.checked_sub(slashed).ok_or(StakingError::Overflow)?;
+
+        // The unstaking fee shrinks with the ticket
+        ticket.fee = (ticket.fee as u128)
+            .checked_mul(remaining as u128)
+            .ok_or(StakingError::Overflow)?
+            .checked_div(ticket.amount as u128)
+            .ok_or(StakingError::Overflow)? as u64;
+        ticket.amount = remaining;
+
+        user_stake.unbonding_amount = user_stake.unbonding_amount.checked_sub(slashed)
+            .ok_or(StakingError::Overflow)?;
+        staking_state.total_unbonding = staking_state.total_unbonding.checked_sub(slashed)
+            .ok_or(StakingError::Overflow)?;
+        user_stake.total_slashed = user_stake.total_slashed.checked_add(slashed)
+            .ok_or(StakingError::Overflow)?;
+        staking_state.total_slashed = staking_state.total_slashed.checked_add(slashed)
+            .ok_or(StakingError::Overflow)?;
+        if freeze {
+            user_stake.status = StakingStatus::Locked;
+        }
+
+        let clock = Clock::get()?;
+        emit_cpi!(StakeSlashed {
+            owner: user_stake.owner,
+            pool: staking_state.key(),
+            position_id,
+            ticket_id: Some(ticket_id),
+            amount: slashed,
+            slash_bps,
+            reason_code,
+            frozen: user_stake.is_frozen(),
+            authority: ctx.accounts.slashing_authority.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        // Move the slashed tokens to treasury
+        if slashed > 0 {
+            let staking_mint = ctx.accounts.staking_state.staking_mint;
+            let pool_id = ctx.accounts.staking_state.pool_id.to_le_bytes();
+            let bump = [ctx.accounts.staking_state.bump];
+            let signer_seeds: &[&[&[u8]]] = &[&[b"staking-state", staking_mint.as_ref(), &pool_id, &bump]];
+            let decimals = ctx.accounts.stake_mint.decimals;
+            transfer_checked_with_hook(ctx.accounts.into_slash_transfer_ctx().with_signer(signer_seeds).with_remaining_accounts(ctx.remaining_accounts.to_vec()), slashed, decimals)?;
+        }
+        Ok(())
+    }
+
+    pub fn unfreeze_stake(ctx: Context<UnfreezeStake>, position_id: u64) -> Result<()> {
+        let user_stake = &mut ctx.accounts.user_stake;
+        require!(user_stake.is_frozen(), StakingError::StakeNotFrozen);
+        user_stake.status = if user_stake.amount > 0 {
+            StakingStatus::Active
+        } else {
+            StakingStatus::Unstaking
+        };
+
+        emit_cpi!(StakeUnfrozen {
+            owner: user_stake.owner,
+            pool: ctx.accounts.staking_state.key(),
+            position_id,
+            authority: ctx.accounts.slashing_authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn compound<'info>(ctx: Context<'_, '_, '_, 'info, Compound<'info>>, position_id: u64) -> Result<()> {
+        require!(!is_paused(&ctx.accounts.staking_state, &ctx.accounts.governance_state), StakingError::Paused);
+        let staking_state = &mut ctx.accounts.staking_state;
+        let user_stake = &mut ctx.accounts.user_stake;
+        require!(!user_stake.is_frozen(), StakingError::StakeFrozen);
+        require!(user_stake.can_claim(staking_state.claim_cooldown), StakingError::ClaimCooldownNotMet);
+
+        let clock = Clock::get()?;
+        staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+        user_stake.settle_rewards(staking_state)?;
+
+        let fee = calculate_fee(user_stake.pending_rewards, ctx.accounts.governance_state.claim_fee_bps)?;
+        let reward = user_stake.pending_rewards.checked_sub(fee)
+            .ok_or(StakingError::Overflow)?;
+        require!(reward > 0, StakingError::InvalidAmount);
+        // Rewards leave the pool value as owed and come back as stake; the fee and any transfer
+        // fee are the difference, and redeem receipts at the rate from before they left
+        let pooled_value = staking_state.pooled_value()?;
+        let pending_rewards = user_stake.pending_rewards;
+
+        staking_state.pay_out_rewards(user_stake.pending_rewards)?;
+        user_stake.pending_rewards = 0;
+        staking_state.total_claim_fees = staking_state.total_claim_fees.checked_add(fee)
+            .ok_or(StakingError::Overflow)?;
+
+        // Move the fee to treasury and the rewards from the reward vault into the stake vault
+        let staking_mint = ctx.accounts.staking_state.staking_mint;
+        let pool_id = ctx.accounts.staking_state.pool_id.to_le_bytes();
+        let bump = [ctx.accounts.staking_state.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"staking-state", staking_mint.as_ref(), &pool_id, &bump]];
+        let decimals = ctx.accounts.reward_mint.decimals;
+        if fee > 0 {
+            transfer_checked_with_hook(ctx.accounts.into_fee_transfer_ctx().with_signer(signer_seeds).with_remaining_accounts(ctx.remaining_accounts.to_vec()), fee, decimals)?;
+        }
+        let vault_before = ctx.accounts.vault_token_account.amount;
+        transfer_checked_with_hook(ctx.accounts.into_compound_transfer_ctx().with_signer(signer_seeds).with_remaining_accounts(ctx.remaining_accounts.to_vec()), reward, decimals)?;
+        let reward = received_amount(&mut ctx.accounts.vault_token_account, vault_before)?;
+
+        let user_stake = &mut ctx.accounts.user_stake;
+        user_stake.add_compounded_stake(&mut ctx.accounts.staking_state, reward, &clock)?;
+
+        let value_out = pending_rewards.checked_sub(reward).ok_or(StakingError::Overflow)?;
+        let receipt_burn = calculate_receipt_redemption(value_out, pooled_value, ctx.accounts.receipt_mint.supply)?;
+        let receipt_burn = ctx.accounts.user_stake.take_receipts(receipt_burn);
+        if receipt_burn > 0 {
+            token_interface::burn(ctx.accounts.into_receipt_burn_ctx(), receipt_burn)?;
+        }
+
+        let user_stake = &ctx.accounts.user_stake;
+        emit_cpi!(RewardsCompounded {
+            owner: user_stake.owner,
+            pool: ctx.accounts.staking_state.key(),
+            position_id,
+            amount: reward,
+            fee,
+            receipts_burned: receipt_burn,
+            new_stake_amount: user_stake.amount,
+            level: user_stake.level,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Slots until the funded rewards run out at the current rate
+    pub fn get_reward_runway(ctx: Context<GetRewardRunway>) -> Result<u64> {
+        ctx.accounts.staking_state.reward_runway(&ctx.accounts.emission_schedule, Clock::get()?.slot)
+    }
+
+    /// Stake tokens per receipt token, scaled by `EXCHANGE_RATE_PRECISION`
+    pub fn get_exchange_rate(ctx: Context<GetExchangeRate>) -> Result<u128> {
+        // Rewards accrued since the last write count toward the pool value too
+        let mut staking_state = (*ctx.accounts.staking_state).clone();
+        staking_state.update_rewards(&ctx.accounts.emission_schedule, Clock::get()?.slot)?;
+        calculate_exchange_rate(staking_state.pooled_value()?, ctx.accounts.receipt_mint.supply)
+    }
+
+    /// Rewards a position could claim now, computed with the same accumulator math as `claim`
+    pub fn get_pending_rewards(ctx: Context<GetPendingRewards>, position_id: u64) -> Result<PendingRewardsInfo> {
+        let clock = Clock::get()?;
+        // Bring copies of the pool and position up to date without writing anything
+        let mut staking_state = (*ctx.accounts.staking_state).clone();
+        let mut user_stake = (*ctx.accounts.user_stake).clone();
+        staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+        user_stake.settle_rewards(&staking_state)?;
+
+        let claim_fee = calculate_fee(user_stake.pending_rewards, ctx.accounts.governance_state.claim_fee_bps)?;
+        Ok(PendingRewardsInfo {
+            owner: user_stake.owner,
+            position_id,
+            pending_rewards: user_stake.pending_rewards,
+            claim_fee,
+            claimable: user_stake.pending_rewards.checked_sub(claim_fee).ok_or(StakingError::Overflow)?,
+            stream_rewards: user_stake.stream_pending_rewards[..staking_state.reward_stream_count as usize].to_vec(),
+            next_claim_at: user_stake.last_claimed_at.saturating_add(staking_state.claim_cooldown),
+            slot: clock.slot,
+        })
+    }
+
+    pub fn get_position(ctx: Context<GetPosition>, position_id: u64) -> Result<PositionInfo> {
+        let user_stake = &ctx.accounts.user_stake;
+        Ok(PositionInfo {
+            owner: user_stake.owner,
+            pool: user_stake.pool,
+            position_id,
+            amount: user_stake.amount,
+            weighted_amount: user_stake.weighted_amount,
+            multiplier: user_stake.get_multiplier(&ctx.accounts.staking_state, Clock::get()?.unix_timestamp)?,
+            level: user_stake.level,
+            status: user_stake.status.clone(),
+            lock_tier: user_stake.lock_tier,
+            lockup_end: user_stake.lockup_end,
+            unbonding_amount: user_stake.unbonding_amount,
+            receipt_amount: user_stake.receipt_amount,
+            staked_at: user_stake.staked_at,
+            last_claimed_at: user_stake.last_claimed_at,
+        })
+    }
+
+    pub fn get_pool_stats(ctx: Context<GetPoolStats>) -> Result<PoolStats> {
+        let clock = Clock::get()?;
+        let mut staking_state = (*ctx.accounts.staking_state).clone();
+        staking_state.update_rewards(&ctx.accounts.emission_schedule, clock.slot)?;
+
+        Ok(PoolStats {
+            pool: ctx.accounts.staking_state.key(),
+            staking_mint: staking_state.staking_mint,
+            pool_id: staking_state.pool_id,
+            reward_mint: staking_state.reward_mint,
+            total_staked: staking_state.total_staked,
+            total_weighted_stake: staking_state.total_weighted_stake,
+            total_unbonding: staking_state.total_unbonding,
+            reward_rate_per_slot: staking_state.current_reward_rate(&ctx.accounts.emission_schedule, clock.slot)?,
+            acc_reward_per_share: staking_state.acc_reward_per_share,
+            total_funded: staking_state.total_funded,
+            total_rewards_owed: staking_state.total_rewards_owed,
+            total_rewards_paid: staking_state.total_rewards_paid,
+            total_emitted: staking_state.total_emitted,
+            runway_slots: staking_state.reward_runway(&ctx.accounts.emission_schedule, clock.slot)?,
+            exchange_rate: calculate_exchange_rate(staking_state.pooled_value()?, ctx.accounts.receipt_mint.supply)?,
+            reward_stream_count: staking_state.reward_stream_count,
+            paused: is_paused(&staking_state, &ctx.accounts.governance_state),
+            slot: clock.slot,
+        })
+    }
+}
+
+/// Whether staking is halted by the admin or by a governance emergency action
+fn is_paused(staking_state: &StakingState, governance_state: &GovernanceState) -> bool {
+    staking_state.paused || governance_state.emergency_paused
+}
+
+/// `transfer_checked` that forwards the context's remaining accounts, so Token-2022 transfer hooks can
+/// resolve their extra accounts. They are matched by key and ignored for mints without a hook.
+fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    spl_token_2022::onchain::invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+/// Amount credited to `account` since `balance_before`, net of any Token-2022 transfer fee
+fn received_amount(account: &mut InterfaceAccount<'_, TokenAccount>, balance_before: u64) -> Result<u64> {
+    account.reload()?;
+    account.amount.checked_sub(balance_before).ok_or_else(|| error!(StakingError::Overflow))
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(pool_id: u64)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + StakingState::SIZE,
+        seeds = [b"staking-state", stake_mint.key().as_ref(), pool_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + EmissionSchedule::SIZE,
+        seeds = [b"emission-schedule", staking_state.key().as_ref()],
+        bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    // Pools are created by the governance authority so nobody can squat a mint's pool ids
+    #[account(
+        seeds = [b"governance-state"],
+        bump = governance_state.bump,
+        seeds::program = botanika_governance::ID,
+        constraint = governance_state.authority == authority.key() @ StakingError::InvalidAuthority
+    )]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"stake-vault", staking_state.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = staking_state,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"reward-vault", staking_state.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = staking_state,
+        token::token_program = reward_token_program
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"receipt-mint", staking_state.key().as_ref()],
+        bump,
+        mint::decimals = stake_mint.decimals,
+        mint::authority = staking_state,
+        mint::token_program = token_program
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(token::mint = stake_mint)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(token::mint = reward_mint)]
+    pub reward_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // Owns the stake and receipt mints; the reward mint may live on the other token program
+    pub token_program: Interface<'info, TokenInterface>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct OpenPosition<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(init_if_needed, payer = user, space = 8 + PositionCounter::SIZE, seeds = [b"position-counter", staking_state.key().as_ref(), user.key().as_ref()], bump)]
+    pub position_counter: Account<'info, PositionCounter>,
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserStake::SIZE,
+        seeds = [b"user-stake", staking_state.key().as_ref(), user.key().as_ref(), position_counter.next_position_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, token::mint = stake_mint)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.vault)]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.treasury, token::mint = stake_mint)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = staking_state.staking_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = staking_state.receipt_mint)]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = receipt_mint, token::authority = user)]
+    pub user_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64)]
+pub struct Stake<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(mut, seeds = [b"user-stake", staking_state.key().as_ref(), user.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, token::mint = stake_mint)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.vault)]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.treasury, token::mint = stake_mint)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = staking_state.staking_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = staking_state.receipt_mint)]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = receipt_mint, token::authority = user)]
+    pub user_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64)]
+pub struct Unstake<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(mut, seeds = [b"user-stake", staking_state.key().as_ref(), user.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UnbondingTicket::SIZE,
+        seeds = [b"unbonding", user_stake.key().as_ref(), user_stake.next_ticket_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, address = staking_state.receipt_mint)]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = receipt_mint, token::authority = user)]
+    pub user_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64)]
+pub struct UnstakeEarly<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(mut, seeds = [b"user-stake", staking_state.key().as_ref(), user.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, token::mint = stake_mint)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.vault)]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.treasury, token::mint = stake_mint)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.reward_vault)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.staking_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = staking_state.receipt_mint)]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = receipt_mint, token::authority = user)]
+    pub user_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64, ticket_id: u64)]
+pub struct Withdraw<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(mut, seeds = [b"user-stake", staking_state.key().as_ref(), user.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(mut, close = user, seeds = [b"unbonding", user_stake.key().as_ref(), ticket_id.to_le_bytes().as_ref()], bump = unbonding_ticket.bump)]
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, token::mint = stake_mint)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.vault)]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.treasury, token::mint = stake_mint)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = staking_state.staking_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64, ticket_id: u64)]
+pub struct CancelUnbonding<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(mut, seeds = [b"user-stake", staking_state.key().as_ref(), user.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(mut, close = user, seeds = [b"unbonding", user_stake.key().as_ref(), ticket_id.to_le_bytes().as_ref()], bump = unbonding_ticket.bump)]
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, address = staking_state.receipt_mint)]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = receipt_mint, token::authority = user)]
+    pub user_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64)]
+pub struct BurnReceipts<'info> {
+    #[account(seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(mut, seeds = [b"user-stake", staking_state.key().as_ref(), user.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    pub user: Signer<'info>,
+    #[account(mut, address = staking_state.receipt_mint)]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = receipt_mint, token::authority = user)]
+    pub user_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64)]
+pub struct CloseStake<'info> {
+    #[account(seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(mut, seeds = [b"position-counter", staking_state.key().as_ref(), user.key().as_ref()], bump = position_counter.bump)]
+    pub position_counter: Account<'info, PositionCounter>,
+    #[account(mut, close = user, seeds = [b"user-stake", staking_state.key().as_ref(), user.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64)]
+pub struct TransferPosition<'info> {
+    #[account(seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(mut, seeds = [b"position-counter", staking_state.key().as_ref(), user.key().as_ref()], bump = position_counter.bump)]
+    pub position_counter: Account<'info, PositionCounter>,
+    #[account(mut, close = user, seeds = [b"user-stake", staking_state.key().as_ref(), user.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + PositionCounter::SIZE,
+        seeds = [b"position-counter", staking_state.key().as_ref(), new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_position_counter: Account<'info, PositionCounter>,
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserStake::SIZE,
+        seeds = [b"user-stake", staking_state.key().as_ref(), new_owner.key().as_ref(), new_position_counter.next_position_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub new_user_stake: Account<'info, UserStake>,
+    /// CHECK: any wallet can receive a position
+    #[account(constraint = new_owner.key() != user.key() @ StakingError::InvalidAuthority)]
+    pub new_owner: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(address = staking_state.receipt_mint)]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = receipt_mint, token::authority = user)]
+    pub user_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = receipt_mint, token::authority = new_owner)]
+    pub new_owner_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64)]
+pub struct SweepStake<'info> {
+    #[account(seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump, has_one = admin)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(mut, seeds = [b"position-counter", staking_state.key().as_ref(), owner.key().as_ref()], bump = position_counter.bump)]
+    pub position_counter: Account<'info, PositionCounter>,
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"user-stake", staking_state.key().as_ref(), owner.key().as_ref(), position_id.to_le_bytes().as_ref()],
+        bump = user_stake.bump,
+        has_one = owner,
+        has_one = payer
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    /// CHECK: only used to derive the position address
+    pub owner: UncheckedAccount<'info>,
+    /// CHECK: rent receiver, must be the account that paid for the position
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64)]
+pub struct Claim<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(mut, seeds = [b"user-stake", staking_state.key().as_ref(), user.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, address = staking_state.reward_vault)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = reward_mint)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.reward_treasury, token::mint = reward_mint)]
+    pub reward_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = staking_state.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = staking_state.receipt_mint)]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = receipt_mint, token::authority = user)]
+    pub user_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64)]
+pub struct Compound<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(mut, seeds = [b"user-stake", staking_state.key().as_ref(), user.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, address = staking_state.reward_vault, constraint = reward_vault.mint == vault_token_account.mint @ StakingError::MintMismatch)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.vault)]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.reward_treasury, token::mint = reward_mint)]
+    pub reward_treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = staking_state.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = staking_state.receipt_mint)]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = receipt_mint, token::authority = user)]
+    pub user_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64)]
+pub struct RefreshPosition<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(mut, seeds = [b"user-stake", staking_state.key().as_ref(), owner.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    /// CHECK: only used to derive the position address
+    pub owner: UncheckedAccount<'info>,
+    pub cranker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct SyncFromGovernance<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct SetPoolTerms<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump, has_one = admin)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct UpdateConfig<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump, has_one = admin)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct SetEmissionSchedule<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump, has_one = admin)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(mut, seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct ProposeAdmin<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump, has_one = admin)]
+    pub staking_state: Account<'info, StakingState>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()],
+        bump = staking_state.bump,
+        constraint = staking_state.pending_admin == pending_admin.key() @ StakingError::InvalidAuthority
+    )]
+    pub staking_state: Account<'info, StakingState>,
+    pub pending_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct FundRewards<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(mut, address = staking_state.reward_vault)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = reward_mint)]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = staking_state.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    pub funder: Signer<'info>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct AddRewardStream<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump, has_one = admin)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"stream-vault", staking_state.key().as_ref(), staking_state.reward_stream_count.to_le_bytes().as_ref()],
+        bump,
+        token::mint = stream_mint,
+        token::authority = staking_state,
+        token::token_program = token_program
+    )]
+    pub stream_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub stream_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct SetTransfersRestricted<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump, has_one = admin)]
+    pub staking_state: Account<'info, StakingState>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct SetPaused<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump, has_one = admin)]
+    pub staking_state: Account<'info, StakingState>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(mut, seeds = [b"user-stake", staking_state.key().as_ref(), user.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, token::mint = stake_mint)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.vault)]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = staking_state.staking_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = staking_state.receipt_mint)]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = receipt_mint, token::authority = user)]
+    pub user_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct GetRewardRunway<'info> {
+    #[account(seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+}
+
+#[derive(Accounts)]
+pub struct GetExchangeRate<'info> {
+    #[account(seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(address = staking_state.receipt_mint)]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_id: u64)]
+pub struct GetPendingRewards<'info> {
+    #[account(seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(seeds = [b"user-stake", staking_state.key().as_ref(), owner.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    /// CHECK: only used to derive the position address
+    pub owner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_id: u64)]
+pub struct GetPosition<'info> {
+    #[account(seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"user-stake", staking_state.key().as_ref(), owner.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    /// CHECK: only used to derive the position address
+    pub owner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetPoolStats<'info> {
+    #[account(seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(seeds = [b"governance-state"], bump = governance_state.bump, seeds::program = botanika_governance::ID)]
+    pub governance_state: Account<'info, GovernanceState>,
+    #[account(address = staking_state.receipt_mint)]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct SetSlashingAuthority<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump, has_one = admin)]
+    pub staking_state: Account<'info, StakingState>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64)]
+pub struct Slash<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump, has_one = slashing_authority)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(seeds = [b"emission-schedule", staking_state.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(mut, seeds = [b"user-stake", staking_state.key().as_ref(), owner.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    /// CHECK: only used to derive the position address
+    pub owner: UncheckedAccount<'info>,
+    pub slashing_authority: Signer<'info>,
+    #[account(mut, address = staking_state.vault)]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.treasury, token::mint = stake_mint)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = staking_state.staking_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64, ticket_id: u64)]
+pub struct SlashUnbonding<'info> {
+    #[account(mut, seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump, has_one = slashing_authority)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(mut, seeds = [b"user-stake", staking_state.key().as_ref(), owner.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(mut, seeds = [b"unbonding", user_stake.key().as_ref(), ticket_id.to_le_bytes().as_ref()], bump = unbonding_ticket.bump)]
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+    /// CHECK: only used to derive the position address
+    pub owner: UncheckedAccount<'info>,
+    pub slashing_authority: Signer<'info>,
+    #[account(mut, address = staking_state.vault)]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = staking_state.treasury, token::mint = stake_mint)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = staking_state.staking_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(position_id: u64)]
+pub struct UnfreezeStake<'info> {
+    #[account(seeds = [b"staking-state", staking_state.staking_mint.as_ref(), staking_state.pool_id.to_le_bytes().as_ref()], bump = staking_state.bump, has_one = slashing_authority)]
+    pub staking_state: Account<'info, StakingState>,
+    #[account(mut, seeds = [b"user-stake", staking_state.key().as_ref(), owner.key().as_ref(), position_id.to_le_bytes().as_ref()], bump = user_stake.bump)]
+    pub user_stake: Account<'info, UserStake>,
+    /// CHECK: only used to derive the position address
+    pub owner: UncheckedAccount<'info>,
+    pub slashing_authority: Signer<'info>,
+}
+
+impl<'info> OpenPosition<'info> {
+    fn into_transfer_to_vault_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.user_token_account.to_account_info(),
+            mint: self.stake_mint.to_account_info(),
+            to: self.vault_token_account.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_fee_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.user_token_account.to_account_info(),
+            mint: self.stake_mint.to_account_info(),
+            to: self.treasury.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_receipt_mint_ctx(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        let cpi_accounts = MintTo {
+            mint: self.receipt_mint.to_account_info(),
+            to: self.user_receipt_account.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> Stake<'info> {
+    fn into_transfer_to_vault_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.user_token_account.to_account_info(),
+            mint: self.stake_mint.to_account_info(),
+            to: self.vault_token_account.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_fee_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.user_token_account.to_account_info(),
+            mint: self.stake_mint.to_account_info(),
+            to: self.treasury.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_receipt_mint_ctx(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        let cpi_accounts = MintTo {
+            mint: self.receipt_mint.to_account_info(),
+            to: self.user_receipt_account.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> Unstake<'info> {
+    fn into_receipt_burn_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        let cpi_accounts = Burn {
+            mint: self.receipt_mint.to_account_info(),
+            from: self.user_receipt_account.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> UnstakeEarly<'info> {
+    fn into_transfer_to_user_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault_token_account.to_account_info(),
+            mint: self.stake_mint.to_account_info(),
+            to: self.user_token_account.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_fee_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault_token_account.to_account_info(),
+            mint: self.stake_mint.to_account_info(),
+            to: self.treasury.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_transfer_to_reward_vault_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault_token_account.to_account_info(),
+            mint: self.stake_mint.to_account_info(),
+            to: self.reward_vault.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_burn_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        let cpi_accounts = Burn {
+            mint: self.stake_mint.to_account_info(),
+            from: self.vault_token_account.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_receipt_burn_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        let cpi_accounts = Burn {
+            mint: self.receipt_mint.to_account_info(),
+            from: self.user_receipt_account.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> Withdraw<'info> {
+    fn into_transfer_to_user_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault_token_account.to_account_info(),
+            mint: self.stake_mint.to_account_info(),
+            to: self.user_token_account.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_fee_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault_token_account.to_account_info(),
+            mint: self.stake_mint.to_account_info(),
+            to: self.treasury.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> CancelUnbonding<'info> {
+    fn into_receipt_mint_ctx(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        let cpi_accounts = MintTo {
+            mint: self.receipt_mint.to_account_info(),
+            to: self.user_receipt_account.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> BurnReceipts<'info> {
+    fn into_receipt_burn_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        let cpi_accounts = Burn {
+            mint: self.receipt_mint.to_account_info(),
+            from: self.user_receipt_account.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> TransferPosition<'info> {
+    fn into_receipt_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.user_receipt_account.to_account_info(),
+            mint: self.receipt_mint.to_account_info(),
+            to: self.new_owner_receipt_account.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> EmergencyWithdraw<'info> {
+    fn into_transfer_to_user_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault_token_account.to_account_info(),
+            mint: self.stake_mint.to_account_info(),
+            to: self.user_token_account.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_receipt_burn_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        let cpi_accounts = Burn {
+            mint: self.receipt_mint.to_account_info(),
+            from: self.user_receipt_account.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> Claim<'info> {
+    fn into_reward_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.reward_vault.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.user_token_account.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.reward_token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_fee_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.reward_vault.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.reward_treasury.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.reward_token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_receipt_burn_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        let cpi_accounts = Burn {
+            mint: self.receipt_mint.to_account_info(),
+            from: self.user_receipt_account.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> FundRewards<'info> {
+    fn into_fund_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.funder_token_account.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.reward_vault.to_account_info(),
+            authority: self.funder.to_account_info(),
+        };
+        CpiContext::new(self.reward_token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> Compound<'info> {
+    fn into_compound_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.reward_vault.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.vault_token_account.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_fee_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.reward_vault.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.reward_treasury.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn into_receipt_burn_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        let cpi_accounts = Burn {
+            mint: self.receipt_mint.to_account_info(),
+            from: self.user_receipt_account.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> Slash<'info> {
+    fn into_slash_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault_token_account.to_account_info(),
+            mint: self.stake_mint.to_account_info(),
+            to: self.treasury.to_account_info(),
+            authority: self.staking_state.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+impl<'info> SlashUnbonding<'info> {
+    fn into_slash_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault_token_account.to_account_info(),
+            mint: self.stake_mint.to_account_info(),
+            to: self.treasury.to_account_info(),
+            authority: self.staking_state.to_account_info(),
         };
         CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
     }
@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::shared_types::*;
+use botanika_common::*;
+use botanika_governance::state::GovernanceState;
+
+use crate::error::StakingError;
 
 #[account]
 #[derive(Default)]
@@ -7,26 +10,75 @@ pub struct StakingState {
     /// Program-level config: authority for staking config update
     pub admin: Pubkey,
 
+    /// Admin nominated by `propose_admin`, takes over once it calls `accept_admin`
+    pub pending_admin: Pubkey,
+
     /// Mint of the staking token (e.g., BONSAI)
     pub staking_mint: Pubkey,
 
+    /// Distinguishes pools sharing a stake mint; the pool PDA is seeded by stake mint and pool id
+    pub pool_id: u64,
+
     /// Vault holding all staked tokens
     pub vault: Pubkey,
 
+    /// Mint of the reward token
+    pub reward_mint: Pubkey,
+
+    /// Vault holding rewards paid out on claim and compound
+    pub reward_vault: Pubkey,
+
+    /// Program-owned mint of the stBONSAI liquid staking receipt
+    pub receipt_mint: Pubkey,
+
     /// Reward authority (can be a multisig or DAO program)
     pub reward_authority: Pubkey,
 
-    /// Reward rate per slot (scaled, e.g., 1e6 == 1 token)
+    /// Authority allowed to slash and freeze stakes (e.g. governance PDA or rewards oracle)
+    pub slashing_authority: Pubkey,
+
+    /// Treasury token account receiving staking and unstaking fees
+    pub treasury: Pubkey,
+
+    /// Treasury token account receiving claim fees (reward mint)
+    pub reward_treasury: Pubkey,
+
+    /// Reward rate per slot (scaled, e.g., 1e6 == 1 token), used while the emission schedule is flat
     pub reward_rate_per_slot: u64,
 
+    /// Cumulative main rewards emitted to stakers, counted against `EmissionSchedule.emission_cap`
+    pub total_emitted: u64,
+
     /// Last updated slot (used to calculate reward delta)
     pub last_update_slot: u64,
 
     /// Total amount staked
     pub total_staked: u64,
 
-    /// Current staking level multipliers (from governance)
-    pub multipliers: [u64; 4], // Bronze, Silver, Gold, Platinum
+    /// Rewards emitted per unit of weighted stake, scaled by `ACC_REWARD_PRECISION`
+    pub acc_reward_per_share: u128,
+
+    /// Sum of all users' multiplier-weighted stake
+    pub total_weighted_stake: u64,
+
+    /// Partner reward streams paid alongside the main reward; only the first `reward_stream_count` are in use
+    pub reward_streams: [RewardStream; MAX_REWARD_STREAMS],
+    pub reward_stream_count: u8,
+
+    /// Staking level tiers (from governance), lowest first; only the first `level_tier_count` are in use
+    pub level_tiers: [LevelTier; MAX_LEVEL_TIERS],
+    pub level_tier_count: u8,
+
+    /// Lock tiers set by the pool admin, used instead of governance's while `custom_lock_tiers` is set
+    pub lock_tiers: [LockTier; LOCK_TIER_COUNT],
+    pub custom_lock_tiers: bool,
+
+    /// While set, `level_tiers` and `lockup_period` are the pool admin's and `sync_from_governance` leaves them alone
+    pub custom_level_tiers: bool,
+    pub custom_lockup_period: bool,
+
+    /// Decimals of the stake mint, used to scale whole-token level thresholds
+    pub stake_decimals: u8,
 
     /// Lockup period in seconds
     pub lockup_period: i64,
@@ -34,6 +86,53 @@ pub struct StakingState {
     /// Claim cooldown in seconds
     pub claim_cooldown: i64,
 
+    /// Window of the time-weighted balance that decides a position's level, in seconds
+    pub level_window: i64,
+
+    /// Loyalty boost (from governance): `loyalty_bps_per_period` per `loyalty_period` staked, capped at `max_loyalty_bps`
+    pub loyalty_bps_per_period: u64,
+    pub loyalty_period: i64,
+    pub max_loyalty_bps: u64,
+
+    /// When level tiers, lockup, cooldown and loyalty boost were last copied from governance
+    pub last_synced_at: i64,
+
+    /// Delay between unstaking and withdrawing, in seconds
+    pub unbonding_period: i64,
+
+    /// Total amount waiting in unbonding tickets
+    pub total_unbonding: u64,
+
+    /// Cumulative fees charged on stake
+    pub total_staking_fees: u64,
+
+    /// Cumulative fees charged on unstake
+    pub total_unstaking_fees: u64,
+
+    /// Cumulative fees charged on claim and compound
+    pub total_claim_fees: u64,
+
+    /// Cumulative early unstake penalties
+    pub total_penalties: u64,
+
+    /// Cumulative slashed stake
+    pub total_slashed: u64,
+
+    /// Rewards accrued to stakers and not yet claimed or compounded
+    pub total_rewards_owed: u64,
+
+    /// Cumulative rewards deposited through `fund_rewards`, plus redistributed penalties
+    pub total_funded: u64,
+
+    /// Cumulative rewards paid out by claim and compound
+    pub total_rewards_paid: u64,
+
+    /// Admin pause; governance can also pause through `GovernanceState.emergency_paused`
+    pub paused: bool,
+
+    /// When set by the admin, `transfer_position` is disabled
+    pub transfers_restricted: bool,
+
     /// Reserved space for future upgrades
     pub bump: u8,
 
@@ -41,7 +140,190 @@ pub struct StakingState {
 }
 
 impl StakingState {
-    pub const SIZE: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 32 + 8 + 8 + 1 + 32;
+    pub const SIZE: usize = 8 + 32 + 32 + 32 + 8 + 32 + 32 + 32 + 32 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 16 + 8 + 416 + 1 + 128 + 1 + 80 + 1 + 1 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 32;
+}
+
+/// How a pool's main reward rate changes over time, and the cap on its total emissions.
+/// One per pool, at the PDA `[b"emission-schedule", staking_state]`.
+#[account]
+#[derive(Default)]
+pub struct EmissionSchedule {
+    /// Pool whose main reward this schedule drives
+    pub pool: Pubkey,
+
+    /// Which of the fields below drives the rate
+    pub curve: EmissionCurve,
+
+    /// Rate segments ordered by start slot; only the first `segment_count` are in use
+    pub segments: [RateSegment; MAX_RATE_SEGMENTS],
+    pub segment_count: u8,
+
+    pub decay: DecayCurve,
+
+    /// Maximum total emission over the pool's lifetime; 0 means uncapped
+    pub emission_cap: u64,
+
+    pub bump: u8,
+}
+
+impl EmissionSchedule {
+    pub const SIZE: usize = 32 + 1 + 128 + 1 + 40 + 8 + 1;
+
+    pub fn new(curve: EmissionCurve, segments: &[RateSegment], decay: DecayCurve, emission_cap: u64) -> Result<Self> {
+        require!(segments.len() <= MAX_RATE_SEGMENTS, StakingError::InvalidEmissionSchedule);
+        let mut schedule = EmissionSchedule {
+            curve,
+            decay,
+            emission_cap,
+            segment_count: segments.len() as u8,
+            ..Default::default()
+        };
+        schedule.segments[..segments.len()].copy_from_slice(segments);
+        schedule.validate()?;
+        Ok(schedule)
+    }
+
+    /// Check the part of the schedule selected by `curve`
+    pub fn validate(&self) -> Result<()> {
+        match self.curve {
+            EmissionCurve::Flat => {}
+            EmissionCurve::Segments => {
+                let segments = self.active_segments();
+                require!(!segments.is_empty(), StakingError::InvalidEmissionSchedule);
+                for segment in segments {
+                    validate_reward_rate(segment.rate_per_slot)?;
+                }
+                require!(
+                    segments.windows(2).all(|pair| pair[0].start_slot < pair[1].start_slot),
+                    StakingError::InvalidEmissionSchedule
+                );
+            }
+            EmissionCurve::Decay => {
+                let decay = &self.decay;
+                validate_reward_rate(decay.initial_rate_per_slot)?;
+                require!(decay.period_slots >= MIN_DECAY_PERIOD_SLOTS, StakingError::InvalidEmissionSchedule);
+                require!(decay.decay_bps > 0 && decay.decay_bps <= 10000, StakingError::InvalidEmissionSchedule);
+                require!(decay.floor_rate_per_slot <= decay.initial_rate_per_slot, StakingError::InvalidEmissionSchedule);
+            }
+        }
+        Ok(())
+    }
+
+    /// The rate segments currently in use
+    pub fn active_segments(&self) -> &[RateSegment] {
+        &self.segments[..self.segment_count as usize]
+    }
+
+    /// Emission rate at `slot`; `flat_rate` applies to the flat curve
+    pub fn rate_at(&self, slot: u64, flat_rate: u64) -> Result<u64> {
+        match self.curve {
+            EmissionCurve::Flat => Ok(flat_rate),
+            EmissionCurve::Segments => Ok(self.active_segments()
+                .iter()
+                .rev()
+                .find(|segment| segment.start_slot <= slot)
+                .map_or(0, |segment| segment.rate_per_slot)),
+            EmissionCurve::Decay => {
+                let decay = &self.decay;
+                if slot < decay.start_slot {
+                    return Ok(0);
+                }
+                let period = (slot - decay.start_slot) / decay.period_slots;
+                calculate_decayed_rate(decay.initial_rate_per_slot, decay.decay_bps, period, decay.floor_rate_per_slot)
+            }
+        }
+    }
+
+    /// Tokens scheduled between `from_slot` and `to_slot`, switching rates at every segment or
+    /// decay period boundary in between. Whole decay periods are summed in closed form, so a long
+    /// gap between updates costs no more than a short one. Saturates at `u64::MAX`; callers clamp
+    /// the result to what is funded anyway.
+    pub fn emission_between(&self, from_slot: u64, to_slot: u64, flat_rate: u64) -> Result<u64> {
+        let mut emitted: u128 = 0;
+        match self.curve {
+            EmissionCurve::Flat => {
+                emitted = (to_slot.saturating_sub(from_slot) as u128) * flat_rate as u128;
+            }
+            EmissionCurve::Segments => {
+                let segments = self.active_segments();
+                for (index, segment) in segments.iter().enumerate() {
+                    let segment_end = segments.get(index + 1).map_or(u64::MAX, |next| next.start_slot);
+                    let from = from_slot.max(segment.start_slot);
+                    let to = to_slot.min(segment_end);
+                    if to > from {
+                        emitted += (to - from) as u128 * segment.rate_per_slot as u128;
+                    }
+                }
+            }
+            EmissionCurve::Decay => {
+                let decay = &self.decay;
+                let from = from_slot.max(decay.start_slot);
+                if to_slot > from {
+                    let rate_in = |period: u64| {
+                        calculate_decayed_rate(decay.initial_rate_per_slot, decay.decay_bps, period, decay.floor_rate_per_slot)
+                    };
+                    let period_start = |period: u64| decay.start_slot + period * decay.period_slots;
+                    let first_period = (from - decay.start_slot) / decay.period_slots;
+                    let last_period = (to_slot - decay.start_slot) / decay.period_slots;
+                    if first_period == last_period {
+                        emitted = (to_slot - from) as u128 * rate_in(first_period)? as u128;
+                    } else {
+                        // Partial periods at either end, whole periods in between
+                        emitted = (period_start(first_period + 1) - from) as u128 * rate_in(first_period)? as u128;
+                        let whole_periods = calculate_decayed_emission(
+                            decay.initial_rate_per_slot,
+                            decay.decay_bps,
+                            first_period + 1,
+                            last_period - first_period - 1,
+                            decay.floor_rate_per_slot,
+                        )?;
+                        emitted += whole_periods * decay.period_slots as u128;
+                        emitted += (to_slot - period_start(last_period)) as u128 * rate_in(last_period)? as u128;
+                    }
+                }
+            }
+        }
+        Ok(u64::try_from(emitted).unwrap_or(u64::MAX))
+    }
+}
+
+/// A partner token emitted to stakers alongside the main reward, between `start_slot` and `end_slot`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq)]
+pub struct RewardStream {
+    /// Mint of the streamed token
+    pub mint: Pubkey,
+
+    /// PDA vault the stream is paid from
+    pub vault: Pubkey,
+
+    /// Tokens emitted per slot while the stream is live
+    pub rate_per_slot: u64,
+
+    /// First slot of emissions
+    pub start_slot: u64,
+
+    /// Slot at which emissions stop
+    pub end_slot: u64,
+
+    /// Stream tokens emitted per unit of weighted stake, scaled by `ACC_REWARD_PRECISION`
+    pub acc_reward_per_share: u128,
+}
+
+impl RewardStream {
+    pub const SIZE: usize = 32 + 32 + 8 + 8 + 8 + 16;
+
+    /// Tokens the stream emits between `from_slot` and `to_slot`
+    pub fn emission_between(&self, from_slot: u64, to_slot: u64) -> Result<u64> {
+        let from_slot = from_slot.max(self.start_slot);
+        let to_slot = to_slot.min(self.end_slot);
+        if to_slot <= from_slot {
+            return Ok(0);
+        }
+        let emitted = ((to_slot - from_slot) as u128)
+            .checked_mul(self.rate_per_slot as u128)
+            .ok_or(StakingError::Overflow)?;
+        u64::try_from(emitted).map_err(|_| error!(StakingError::Overflow))
+    }
 }
 
 #[account]
@@ -50,22 +332,43 @@ pub struct UserStake {
     /// The user who owns this stake account
     pub owner: Pubkey,
 
-    /// The amount staked by this user
+    /// Staking pool this position belongs to
+    pub pool: Pubkey,
+
+    /// Index of this position among the owner's positions
+    pub position_id: u64,
+
+    /// Account that paid rent for this position, refunded when it is swept
+    pub payer: Pubkey,
+
+    /// The amount staked in this position
     pub amount: u64,
 
-    /// Accumulated reward (claimable)
+    /// Rewards already accounted for at the current accumulator value
     pub reward_debt: u64,
 
+    /// Multiplier-weighted stake used for reward accounting
+    pub weighted_amount: u64,
+
+    /// Settled rewards not yet claimed
+    pub pending_rewards: u64,
+
     /// Last slot when reward was updated
     pub last_updated_slot: u64,
 
-    /// Current staking level
-    pub level: StakingLevel,
+    /// Current staking level (index into the level tier table)
+    pub level: u8,
+
+    /// Average balance over the last `level_window` seconds, used to pick the level
+    pub twab_amount: u64,
+
+    /// When `twab_amount` was last brought up to date
+    pub twab_updated_at: i64,
 
     /// Current staking status
     pub status: StakingStatus,
 
-    /// Timestamp when staking started
+    /// Start of the current continuous staking period; reset when the position is fully unstaked
     pub staked_at: i64,
 
     /// Last time rewards were claimed
@@ -74,25 +377,392 @@ pub struct UserStake {
     /// Lockup end timestamp
     pub lockup_end: i64,
 
+    /// Index of the selected lock tier
+    pub lock_tier: u8,
+
+    /// Lockup duration committed to by the selected tier, in seconds
+    pub lock_duration: i64,
+
+    /// Boost earned by the selected lock tier
+    pub lock_multiplier: u64,
+
+    /// Amount held in this position's unbonding tickets
+    pub unbonding_amount: u64,
+
+    /// Id assigned to the next unbonding ticket
+    pub next_ticket_id: u64,
+
+    /// Total amount slashed from this position
+    pub total_slashed: u64,
+
+    /// stBONSAI minted for this position and not yet burned
+    pub receipt_amount: u64,
+
+    /// Per-stream counterparts of `reward_debt` and `pending_rewards`, indexed like `StakingState.reward_streams`
+    pub stream_reward_debt: [u64; MAX_REWARD_STREAMS],
+    pub stream_pending_rewards: [u64; MAX_REWARD_STREAMS],
+
+    /// Reserved space for future upgrades
+    pub bump: u8,
+
+    pub _reserved: [u8; 16],
+}
+
+#[account]
+#[derive(Default)]
+pub struct PositionCounter {
+    /// The user who owns the positions
+    pub owner: Pubkey,
+
+    /// Id assigned to the next opened position
+    pub next_position_id: u64,
+
+    /// Number of positions currently open
+    pub open_positions: u64,
+
     /// Reserved space for future upgrades
     pub bump: u8,
 
     pub _reserved: [u8; 16],
 }
 
+impl PositionCounter {
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 1 + 16;
+}
+
+#[account]
+#[derive(Default)]
+pub struct UnbondingTicket {
+    /// The user who owns the unbonding tokens
+    pub owner: Pubkey,
+
+    /// Position the tokens were unstaked from
+    pub position_id: u64,
+
+    /// Index of this ticket within the position
+    pub ticket_id: u64,
+
+    /// Amount waiting to be withdrawn
+    pub amount: u64,
+
+    /// Unstaking fee deducted on withdraw
+    pub fee: u64,
+
+    /// Ticket status (always `Unstaking` while open)
+    pub status: StakingStatus,
+
+    /// Timestamp when the tokens were unstaked
+    pub created_at: i64,
+
+    /// Timestamp after which the tokens can be withdrawn
+    pub unlock_at: i64,
+
+    /// Reserved space for future upgrades
+    pub bump: u8,
+
+    pub _reserved: [u8; 16],
+}
+
+impl UnbondingTicket {
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 1 + 16;
+}
+
+impl UnbondingTicket {
+    /// Check if the unbonding period has elapsed
+    pub fn is_unlocked(&self) -> bool {
+        let now = Clock::get().unwrap().unix_timestamp;
+        now >= self.unlock_at
+    }
+}
+
+impl StakingState {
+    /// Copy the governance-owned staking parameters into this config, except those the pool admin overrides
+    pub fn sync_from_governance(&mut self, governance_state: &GovernanceState, now: i64) -> Result<()> {
+        validate_lockup_period(governance_state.lockup_period)?;
+        validate_claim_cooldown(governance_state.claim_cooldown)?;
+        validate_loyalty_boost(
+            governance_state.loyalty_bps_per_period,
+            governance_state.loyalty_period,
+            governance_state.max_loyalty_bps,
+        )?;
+
+        if !self.custom_level_tiers {
+            self.set_level_tiers(governance_state.active_level_tiers())?;
+        }
+        if !self.custom_lockup_period {
+            self.lockup_period = governance_state.lockup_period;
+        }
+        self.claim_cooldown = governance_state.claim_cooldown;
+        self.loyalty_bps_per_period = governance_state.loyalty_bps_per_period;
+        self.loyalty_period = governance_state.loyalty_period;
+        self.max_loyalty_bps = governance_state.max_loyalty_bps;
+        self.last_synced_at = now;
+        Ok(())
+    }
+
+    /// The level tiers currently in use
+    pub fn active_level_tiers(&self) -> &[LevelTier] {
+        &self.level_tiers[..self.level_tier_count as usize]
+    }
+
+    /// Replace the level tier table
+    pub fn set_level_tiers(&mut self, tiers: &[LevelTier]) -> Result<()> {
+        validate_level_tiers(tiers)?;
+        self.level_tiers = [LevelTier::default(); MAX_LEVEL_TIERS];
+        self.level_tiers[..tiers.len()].copy_from_slice(tiers);
+        self.level_tier_count = tiers.len() as u8;
+        Ok(())
+    }
+
+    /// Lock tier `index`: from the pool's own table if the admin set one, otherwise from governance's
+    pub fn lock_tier(&self, governance_state: &GovernanceState, index: u8) -> Result<LockTier> {
+        let tiers = if self.custom_lock_tiers {
+            &self.lock_tiers
+        } else {
+            &governance_state.lock_tiers
+        };
+        tiers.get(index as usize).copied().ok_or_else(|| error!(StakingError::InvalidLockTier))
+    }
+
+    /// Replace the pool's own lock tier table and use it instead of governance's
+    pub fn set_lock_tiers(&mut self, tiers: &[LockTier]) -> Result<()> {
+        require!(tiers.len() == LOCK_TIER_COUNT, StakingError::InvalidLockTier);
+        for tier in tiers {
+            require!(tier.duration > 0, StakingError::InvalidLockTier);
+            validate_lockup_period(tier.duration)?;
+            validate_multiplier(tier.multiplier)?;
+        }
+        self.lock_tiers.copy_from_slice(tiers);
+        self.custom_lock_tiers = true;
+        Ok(())
+    }
+
+    /// Accrue emissions since `last_update_slot` into the reward-per-share accumulator
+    pub fn update_rewards(&mut self, schedule: &EmissionSchedule, current_slot: u64) -> Result<()> {
+        if current_slot <= self.last_update_slot {
+            return Ok(());
+        }
+
+        let emitted = self.pending_emission(schedule, current_slot)?;
+        if emitted > 0 {
+            let increment = (emitted as u128)
+                .checked_mul(ACC_REWARD_PRECISION)
+                .ok_or(StakingError::Overflow)?
+                .checked_div(self.total_weighted_stake as u128)
+                .ok_or(StakingError::Overflow)?;
+            self.acc_reward_per_share = self.acc_reward_per_share
+                .checked_add(increment)
+                .ok_or(StakingError::Overflow)?;
+            self.total_rewards_owed = self.total_rewards_owed
+                .checked_add(emitted)
+                .ok_or(StakingError::Overflow)?;
+            self.total_emitted = self.total_emitted
+                .checked_add(emitted)
+                .ok_or(StakingError::Overflow)?;
+        }
+
+        if self.total_weighted_stake > 0 {
+            let (last_update_slot, total_weighted_stake) = (self.last_update_slot, self.total_weighted_stake);
+            for stream in self.reward_streams[..self.reward_stream_count as usize].iter_mut() {
+                let emitted = stream.emission_between(last_update_slot, current_slot)?;
+                let increment = (emitted as u128)
+                    .checked_mul(ACC_REWARD_PRECISION)
+                    .ok_or(StakingError::Overflow)?
+                    .checked_div(total_weighted_stake as u128)
+                    .ok_or(StakingError::Overflow)?;
+                stream.acc_reward_per_share = stream.acc_reward_per_share
+                    .checked_add(increment)
+                    .ok_or(StakingError::Overflow)?;
+            }
+        }
+
+        self.last_update_slot = current_slot;
+        Ok(())
+    }
+
+    /// The reward streams currently in use
+    pub fn active_reward_streams(&self) -> &[RewardStream] {
+        &self.reward_streams[..self.reward_stream_count as usize]
+    }
+
+    /// Index of the stream paid from `vault`
+    pub fn find_reward_stream(&self, vault: &Pubkey) -> Option<usize> {
+        self.active_reward_streams().iter().position(|stream| stream.vault == *vault)
+    }
+
+    /// Rewards emitted since `last_update_slot` that `update_rewards` has not yet accrued.
+    /// Emissions stop once the funded rewards or the emission cap are used up, so claims never
+    /// outrun the vault.
+    pub fn pending_emission(&self, schedule: &EmissionSchedule, current_slot: u64) -> Result<u64> {
+        if current_slot <= self.last_update_slot || self.total_weighted_stake == 0 {
+            return Ok(0);
+        }
+        let scheduled = schedule.emission_between(
+            self.last_update_slot,
+            current_slot,
+            self.reward_rate_per_slot,
+        )?;
+        Ok(scheduled.min(self.unallocated_rewards()).min(self.remaining_emission_cap(schedule)))
+    }
+
+    /// Emissions left under the schedule's lifetime cap
+    pub fn remaining_emission_cap(&self, schedule: &EmissionSchedule) -> u64 {
+        match schedule.emission_cap {
+            0 => u64::MAX,
+            cap => cap.saturating_sub(self.total_emitted),
+        }
+    }
+
+    /// Main reward rate at `slot` under the emission schedule
+    pub fn current_reward_rate(&self, schedule: &EmissionSchedule, slot: u64) -> Result<u64> {
+        schedule.rate_at(slot, self.reward_rate_per_slot)
+    }
+
+    /// Funded rewards not yet emitted to stakers
+    pub fn unallocated_rewards(&self) -> u64 {
+        self.total_funded
+            .saturating_sub(self.total_rewards_paid)
+            .saturating_sub(self.total_rewards_owed)
+    }
+
+    /// Slots until the funded rewards or the emission cap run out at the current rate;
+    /// `u64::MAX` when nothing is emitted
+    pub fn reward_runway(&self, schedule: &EmissionSchedule, current_slot: u64) -> Result<u64> {
+        let rate = self.current_reward_rate(schedule, current_slot)?;
+        if rate == 0 {
+            return Ok(u64::MAX);
+        }
+        let remaining = self.unallocated_rewards()
+            .min(self.remaining_emission_cap(schedule))
+            .saturating_sub(self.pending_emission(schedule, current_slot)?);
+        Ok(remaining / rate)
+    }
+
+    /// Record rewards deposited into the reward vault
+    pub fn fund_rewards(&mut self, amount: u64) -> Result<()> {
+        self.total_funded = self.total_funded
+            .checked_add(amount)
+            .ok_or(StakingError::Overflow)?;
+        Ok(())
+    }
+
+    /// Value backing the stBONSAI supply: the stake held in positions plus, when rewards are paid in
+    /// the stake token, the rewards accrued to them and not yet paid out. Run `update_rewards` first.
+    pub fn pooled_value(&self) -> Result<u64> {
+        if self.reward_mint != self.staking_mint {
+            return Ok(self.total_staked);
+        }
+        self.total_staked
+            .checked_add(self.total_rewards_owed)
+            .ok_or_else(|| error!(StakingError::Overflow))
+    }
+
+    /// Whether claimed rewards are part of `pooled_value` and must redeem receipts when paid out
+    pub fn rewards_back_receipts(&self) -> bool {
+        self.reward_mint == self.staking_mint
+    }
+
+    /// stBONSAI worth `value` at the current rate, burned from `position` when that much of its stake
+    /// or rewards leaves the pool, so the rate stays put for every other holder
+    pub fn receipt_redemption(&self, position: &UserStake, value: u64, receipt_supply: u64) -> Result<u64> {
+        let receipts = calculate_receipt_redemption(value, self.pooled_value()?, receipt_supply)?;
+        Ok(receipts.min(position.receipt_amount))
+    }
+
+    /// Record rewards leaving the reward vault through claim or compound
+    pub fn pay_out_rewards(&mut self, amount: u64) -> Result<()> {
+        self.release_rewards(amount);
+        self.total_rewards_paid = self.total_rewards_paid
+            .checked_add(amount)
+            .ok_or(StakingError::Overflow)?;
+        Ok(())
+    }
+
+    /// Drop `amount` from the rewards owed to stakers. Rewards released without being paid,
+    /// e.g. forfeited ones, become available to emit again.
+    pub fn release_rewards(&mut self, amount: u64) {
+        // Accumulator rounding can leave users' settled rewards a few units above the tally
+        self.total_rewards_owed = self.total_rewards_owed.saturating_sub(amount);
+    }
+
+    /// Distribute `amount` of extra rewards pro rata to the current weighted stake
+    pub fn distribute_rewards(&mut self, amount: u64) -> Result<()> {
+        require!(self.total_weighted_stake > 0, StakingError::NoStakers);
+        let increment = (amount as u128)
+            .checked_mul(ACC_REWARD_PRECISION)
+            .ok_or(StakingError::Overflow)?
+            .checked_div(self.total_weighted_stake as u128)
+            .ok_or(StakingError::Overflow)?;
+        self.acc_reward_per_share = self.acc_reward_per_share
+            .checked_add(increment)
+            .ok_or(StakingError::Overflow)?;
+        self.total_rewards_owed = self.total_rewards_owed
+            .checked_add(amount)
+            .ok_or(StakingError::Overflow)?;
+        // The distributed tokens are moved into the reward vault by the caller
+        self.fund_rewards(amount)
+    }
+}
+
 impl UserStake {
-    pub const SIZE: usize = 8 + 32 + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 1 + 16;
+    pub const SIZE: usize = 8 + 32 + 32 + 8 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 1 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 32 + 1 + 16;
 }
 
 impl UserStake {
-    /// Calculate staking level based on amount
-    pub fn calculate_level(&self) -> StakingLevel {
-        match self.amount {
-            0..=1000 => StakingLevel::Bronze,
-            1001..=5000 => StakingLevel::Silver,
-            5001..=10000 => StakingLevel::Gold,
-            _ => StakingLevel::Platinum,
-        }
+    /// Calculate staking level from the time-weighted balance and the configured tiers.
+    /// The current amount caps it, so unstaking drops the level straight away.
+    pub fn calculate_level(&self, state: &StakingState) -> u8 {
+        let balance = self.amount.min(self.twab_amount);
+        calculate_staking_level(balance, state.active_level_tiers(), state.stake_decimals)
+    }
+
+    /// Fold the balance held since `twab_updated_at` into the time-weighted average.
+    /// Must run before `amount` changes.
+    pub fn accrue_twab(&mut self, level_window: i64, now: i64) -> Result<()> {
+        let elapsed = now.saturating_sub(self.twab_updated_at);
+        self.twab_amount = calculate_time_weighted_balance(self.twab_amount, self.amount, elapsed, level_window)?;
+        self.twab_updated_at = now;
+        Ok(())
+    }
+
+    /// Re-evaluate the level from the latest time-weighted balance.
+    /// Rewards must be settled before calling this.
+    pub fn refresh_level(&mut self, state: &mut StakingState, clock: &Clock) -> Result<()> {
+        self.accrue_twab(state.level_window, clock.unix_timestamp)?;
+        self.update_weight(state, clock.unix_timestamp)
+    }
+
+    /// Check if nothing is left in this position: no stake, no receipts, no unbonding tickets and no rewards
+    pub fn is_empty(&self) -> bool {
+        self.amount == 0
+            && self.receipt_amount == 0
+            && self.unbonding_amount == 0
+            && self.pending_rewards == 0
+            && self.stream_pending_rewards.iter().all(|pending| *pending == 0)
+    }
+
+    /// Take `receipts` off this position, or all it holds once it has no stake or rewards left to
+    /// redeem them against. Returns how many to burn.
+    pub fn take_receipts(&mut self, receipts: u64) -> u64 {
+        let receipts = if self.amount == 0 && self.pending_rewards == 0 {
+            self.receipt_amount
+        } else {
+            receipts.min(self.receipt_amount)
+        };
+        self.receipt_amount -= receipts;
+        receipts
+    }
+
+    /// Check if the position is empty and its owner has not staked, unstaked, claimed or
+    /// refreshed it for `SWEEP_INACTIVITY_PERIOD`
+    pub fn is_abandoned(&self, now: i64) -> bool {
+        let last_active_at = self.twab_updated_at.max(self.last_claimed_at);
+        self.is_empty() && now.saturating_sub(last_active_at) >= SWEEP_INACTIVITY_PERIOD
+    }
+
+    /// Check if the stake is frozen pending slashing review
+    pub fn is_frozen(&self) -> bool {
+        self.status == StakingStatus::Locked
     }
 
     /// Check if lockup period is met
@@ -101,19 +771,567 @@ impl UserStake {
         now >= self.lockup_end
     }
 
-    /// Check if claim cooldown is met
-    pub fn can_claim(&self) -> bool {
+    /// Check if the configured claim cooldown is met
+    pub fn can_claim(&self, claim_cooldown: i64) -> bool {
         let now = Clock::get().unwrap().unix_timestamp;
-        now >= self.last_claimed_at + CLAIM_COOLDOWN
+        now >= self.last_claimed_at + claim_cooldown
+    }
+
+    /// Commit this position to a lock tier. `lockup_period` acts as the minimum lockup,
+    /// and a position can move to a longer tier but never a shorter one.
+    pub fn set_lock_tier(&mut self, tier_index: u8, tier: &LockTier, lockup_period: i64) -> Result<()> {
+        require!(tier.multiplier > 0, StakingError::InvalidLockTier);
+        let lock_duration = tier.duration.max(lockup_period);
+        require!(lock_duration >= self.lock_duration, StakingError::LockTierDowngrade);
+
+        self.lock_tier = tier_index;
+        self.lock_duration = lock_duration;
+        self.lock_multiplier = tier.multiplier;
+        Ok(())
+    }
+
+    /// Add `amount` to this position, settling rewards on the previous balance first.
+    /// A top-up extends the lockup to cover the new tokens but never shortens it.
+    pub fn add_stake(&mut self, state: &mut StakingState, schedule: &EmissionSchedule, amount: u64, clock: &Clock) -> Result<()> {
+        require!(!self.is_frozen(), StakingError::StakeFrozen);
+        state.update_rewards(schedule, clock.slot)?;
+        self.settle_rewards(state)?;
+        self.accrue_twab(state.level_window, clock.unix_timestamp)?;
+        // A top-up keeps the loyalty clock running; staking into an empty position restarts it
+        if self.amount == 0 {
+            self.staked_at = clock.unix_timestamp;
+        }
+
+        state.total_staked = state.total_staked
+            .checked_add(amount)
+            .ok_or(StakingError::Overflow)?;
+        self.amount = self.amount
+            .checked_add(amount)
+            .ok_or(StakingError::Overflow)?;
+
+        let lockup_end = clock.unix_timestamp
+            .checked_add(self.lock_duration)
+            .ok_or(StakingError::Overflow)?;
+        self.lockup_end = self.lockup_end.max(lockup_end);
+        self.status = StakingStatus::Active;
+        self.last_updated_slot = clock.slot;
+        self.update_weight(state, clock.unix_timestamp)
+    }
+
+    /// Remove `amount` from this position, settling rewards on the previous balance first
+    pub fn remove_stake(&mut self, state: &mut StakingState, schedule: &EmissionSchedule, amount: u64, clock: &Clock) -> Result<()> {
+        state.update_rewards(schedule, clock.slot)?;
+        self.settle_rewards(state)?;
+        self.accrue_twab(state.level_window, clock.unix_timestamp)?;
+
+        state.total_staked = state.total_staked
+            .checked_sub(amount)
+            .ok_or(StakingError::Overflow)?;
+        self.amount = self.amount
+            .checked_sub(amount)
+            .ok_or(StakingError::Overflow)?;
+        self.last_updated_slot = clock.slot;
+        self.update_weight(state, clock.unix_timestamp)
+    }
+
+    /// Restake compounded rewards into this position without extending its lockup.
+    /// Rewards must already be settled and taken out of `pending_rewards`.
+    pub fn add_compounded_stake(&mut self, state: &mut StakingState, amount: u64, clock: &Clock) -> Result<()> {
+        require!(amount > 0, StakingError::InvalidAmount);
+        self.accrue_twab(state.level_window, clock.unix_timestamp)?;
+        state.total_staked = state.total_staked
+            .checked_add(amount)
+            .ok_or(StakingError::Overflow)?;
+        self.amount = self.amount
+            .checked_add(amount)
+            .ok_or(StakingError::Overflow)?;
+        self.last_claimed_at = clock.unix_timestamp;
+        self.last_updated_slot = clock.slot;
+        self.update_weight(state, clock.unix_timestamp)
+    }
+
+    /// Pull the whole position out of the pool without touching reward accounting.
+    /// Rewards accrued since the last settlement are forfeited; `pending_rewards` stays claimable.
+    pub fn emergency_exit(&mut self, state: &mut StakingState) -> Result<u64> {
+        let amount = self.amount;
+        state.total_staked = state.total_staked
+            .checked_sub(amount)
+            .ok_or(StakingError::Overflow)?;
+        state.total_weighted_stake = state.total_weighted_stake
+            .checked_sub(self.weighted_amount)
+            .ok_or(StakingError::Overflow)?;
+        let forfeited = calculate_pending_reward(self.weighted_amount, state.acc_reward_per_share, self.reward_debt)
+            .unwrap_or(0);
+        state.release_rewards(forfeited);
+
+        self.amount = 0;
+        self.weighted_amount = 0;
+        self.reward_debt = 0;
+        self.stream_reward_debt = [0; MAX_REWARD_STREAMS];
+        self.level = self.calculate_level(state);
+        Ok(amount)
     }
 
-    /// Get multiplier for current level
-    pub fn get_multiplier(&self, state: &StakingState) -> u64 {
-        match self.level {
-            StakingLevel::Bronze => state.multipliers[0],
-            StakingLevel::Silver => state.multipliers[1],
-            StakingLevel::Gold => state.multipliers[2],
-            StakingLevel::Platinum => state.multipliers[3],
+    /// Move rewards accrued since the last settlement into `pending_rewards`, and each
+    /// stream's into `stream_pending_rewards`
+    pub fn settle_rewards(&mut self, state: &StakingState) -> Result<()> {
+        let accrued = calculate_pending_reward(
+            self.weighted_amount,
+            state.acc_reward_per_share,
+            self.reward_debt,
+        )?;
+        self.pending_rewards = self.pending_rewards
+            .checked_add(accrued)
+            .ok_or(StakingError::Overflow)?;
+        self.reward_debt = calculate_reward_debt(self.weighted_amount, state.acc_reward_per_share)?;
+
+        for (index, stream) in state.active_reward_streams().iter().enumerate() {
+            let accrued = calculate_pending_reward(
+                self.weighted_amount,
+                stream.acc_reward_per_share,
+                self.stream_reward_debt[index],
+            )?;
+            self.stream_pending_rewards[index] = self.stream_pending_rewards[index]
+                .checked_add(accrued)
+                .ok_or(StakingError::Overflow)?;
+            self.stream_reward_debt[index] = calculate_reward_debt(self.weighted_amount, stream.acc_reward_per_share)?;
         }
+        Ok(())
+    }
+
+    /// Recompute level and weighted stake after `amount` changed, keeping pool totals in sync.
+    /// Rewards must be settled before calling this.
+    pub fn update_weight(&mut self, state: &mut StakingState, now: i64) -> Result<()> {
+        self.level = self.calculate_level(state);
+        let weighted_amount = calculate_weighted_amount(self.amount, self.get_multiplier(state, now)?)?;
+
+        state.total_weighted_stake = state.total_weighted_stake
+            .checked_sub(self.weighted_amount)
+            .ok_or(StakingError::Overflow)?
+            .checked_add(weighted_amount)
+            .ok_or(StakingError::Overflow)?;
+
+        self.weighted_amount = weighted_amount;
+        self.reward_debt = calculate_reward_debt(weighted_amount, state.acc_reward_per_share)?;
+        for (index, stream) in state.active_reward_streams().iter().enumerate() {
+            self.stream_reward_debt[index] = calculate_reward_debt(weighted_amount, stream.acc_reward_per_share)?;
+        }
+        Ok(())
+    }
+
+    /// Get multiplier for current level, boosted by the position's lock tier and loyalty
+    pub fn get_multiplier(&self, state: &StakingState, now: i64) -> Result<u64> {
+        let level_multiplier = state.active_level_tiers()
+            .get(self.level as usize)
+            .map_or(BASE_MULTIPLIER, |tier| tier.multiplier);
+        let lock_multiplier = if self.lock_multiplier == 0 {
+            BASE_MULTIPLIER
+        } else {
+            self.lock_multiplier
+        };
+        let loyalty_multiplier = calculate_loyalty_multiplier(
+            now.saturating_sub(self.staked_at),
+            state.loyalty_bps_per_period,
+            state.loyalty_period,
+            state.max_loyalty_bps,
+        )?;
+        let multiplier = (level_multiplier as u128)
+            .checked_mul(lock_multiplier as u128)
+            .ok_or(StakingError::Overflow)?
+            .checked_div(BASE_MULTIPLIER as u128)
+            .ok_or(StakingError::Overflow)?
+            .checked_mul(loyalty_multiplier as u128)
+            .ok_or(StakingError::Overflow)?
+            .checked_div(BASE_MULTIPLIER as u128)
+            .ok_or(StakingError::Overflow)?;
+        u64::try_from(multiplier).map_err(|_| error!(StakingError::Overflow))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Far enough past the epoch that a new position's time-weighted balance starts from zero
+    const START: i64 = 10 * LEVEL_WINDOW;
+
+    fn clock(slot: u64, unix_timestamp: i64) -> Clock {
+        Clock { slot, unix_timestamp, ..Default::default() }
+    }
+
+    /// A funded pool on the default level tiers, emitting 100 tokens per slot
+    fn funded_pool() -> StakingState {
+        let mut state = StakingState {
+            reward_rate_per_slot: 100,
+            total_funded: 1_000_000_000,
+            level_window: LEVEL_WINDOW,
+            ..Default::default()
+        };
+        state.set_level_tiers(&DEFAULT_LEVEL_TIERS[..DEFAULT_LEVEL_TIER_COUNT as usize]).unwrap();
+        state
+    }
+
+    fn segments_schedule() -> EmissionSchedule {
+        let segments = [
+            RateSegment { start_slot: 100, rate_per_slot: 10 },
+            RateSegment { start_slot: 200, rate_per_slot: 20 },
+        ];
+        EmissionSchedule::new(EmissionCurve::Segments, &segments, DecayCurve::default(), 0).unwrap()
+    }
+
+    fn decay_schedule(start_slot: u64, floor_rate_per_slot: u64) -> EmissionSchedule {
+        let decay = DecayCurve {
+            start_slot,
+            period_slots: MIN_DECAY_PERIOD_SLOTS,
+            initial_rate_per_slot: 1_000_000,
+            decay_bps: 5000,
+            floor_rate_per_slot,
+        };
+        EmissionSchedule::new(EmissionCurve::Decay, &[], decay, 0).unwrap()
+    }
+
+    /// Reference sum walking one decay period at a time
+    fn decay_emission_by_period(schedule: &EmissionSchedule, from_slot: u64, to_slot: u64) -> u128 {
+        let decay = &schedule.decay;
+        let mut emitted = 0u128;
+        let mut from = from_slot.max(decay.start_slot);
+        while from < to_slot {
+            let period = (from - decay.start_slot) / decay.period_slots;
+            let to = to_slot.min(decay.start_slot + (period + 1) * decay.period_slots);
+            emitted += (to - from) as u128 * schedule.rate_at(from, 0).unwrap() as u128;
+            from = to;
+        }
+        emitted
+    }
+
+    #[test]
+    fn segment_emission_switches_rate_at_boundaries() {
+        let schedule = segments_schedule();
+        assert_eq!(schedule.emission_between(0, 100, 0).unwrap(), 0);
+        assert_eq!(schedule.emission_between(150, 160, 0).unwrap(), 10 * 10);
+        assert_eq!(schedule.emission_between(50, 250, 0).unwrap(), 100 * 10 + 50 * 20);
+        assert_eq!(schedule.emission_between(190, 210, 0).unwrap(), 10 * 10 + 10 * 20);
+    }
+
+    #[test]
+    fn decay_emission_across_period_boundaries() {
+        let period = MIN_DECAY_PERIOD_SLOTS;
+        let schedule = decay_schedule(1_000, 0);
+        assert_eq!(schedule.emission_between(0, 1_000, 0).unwrap(), 0);
+        assert_eq!(schedule.emission_between(500, 1_010, 0).unwrap(), 10 * 1_000_000);
+        // Straddling one boundary pays each side at its own rate
+        let boundary = 1_000 + period;
+        assert_eq!(schedule.emission_between(boundary - 5, boundary + 5, 0).unwrap(), 5 * 1_000_000 + 5 * 500_000);
+        // Exactly the second period
+        assert_eq!(schedule.emission_between(boundary, boundary + period, 0).unwrap(), period * 500_000);
+    }
+
+    #[test]
+    fn decay_emission_over_many_periods_matches_walking_them() {
+        let period = MIN_DECAY_PERIOD_SLOTS;
+        for floor in [0, 1_000] {
+            let schedule = decay_schedule(1_000, floor);
+            let (from, to) = (1_000 + period / 3, 1_000 + 30 * period + period / 2);
+            let expected = decay_emission_by_period(&schedule, from, to);
+            let actual = schedule.emission_between(from, to, 0).unwrap() as u128;
+            // Whole periods are summed without rounding each rate down first
+            assert!(actual >= expected && actual - expected <= 30 * period as u128, "{actual} vs {expected}");
+        }
+    }
+
+    #[test]
+    fn decay_emission_over_a_long_gap_settles_on_the_floor() {
+        let period = MIN_DECAY_PERIOD_SLOTS;
+        let schedule = decay_schedule(0, 1_000);
+        let after_decay = schedule.emission_between(0, 100 * period, 0).unwrap();
+        let much_later = schedule.emission_between(0, 1_000_000 * period, 0).unwrap();
+        assert_eq!(much_later - after_decay, (1_000_000 - 100) * period * 1_000);
+    }
+
+    #[test]
+    fn long_idle_gap_at_max_rate_stops_at_funded_rewards() {
+        let schedule = EmissionSchedule::default();
+        let mut state = StakingState {
+            reward_rate_per_slot: MAX_REWARD_RATE_PER_SLOT,
+            total_weighted_stake: 1_000,
+            total_funded: 5_000_000,
+            ..Default::default()
+        };
+        // Schedules more than fits in a u64
+        let idle_slots = 20_000_000;
+        assert_eq!(schedule.emission_between(0, idle_slots, MAX_REWARD_RATE_PER_SLOT).unwrap(), u64::MAX);
+        state.update_rewards(&schedule, idle_slots).unwrap();
+        assert_eq!(state.total_emitted, 5_000_000);
+        assert_eq!(state.unallocated_rewards(), 0);
+    }
+
+    #[test]
+    fn accumulator_splits_emission_by_weighted_stake() {
+        let schedule = EmissionSchedule::default();
+        let mut state = funded_pool();
+        let (mut alice, mut bob) = (UserStake::default(), UserStake::default());
+        alice.add_stake(&mut state, &schedule, 600, &clock(0, START)).unwrap();
+        bob.add_stake(&mut state, &schedule, 200, &clock(0, START)).unwrap();
+        // Both start at Bronze (1.2x)
+        assert_eq!((alice.weighted_amount, bob.weighted_amount), (720, 240));
+        assert_eq!(state.total_weighted_stake, 960);
+
+        state.update_rewards(&schedule, 96).unwrap();
+        assert_eq!(state.acc_reward_per_share, 10 * ACC_REWARD_PRECISION);
+        assert_eq!(state.total_rewards_owed, 9_600);
+
+        alice.settle_rewards(&state).unwrap();
+        bob.settle_rewards(&state).unwrap();
+        assert_eq!((alice.pending_rewards, bob.pending_rewards), (7_200, 2_400));
+        // Settling again without new emissions adds nothing
+        alice.settle_rewards(&state).unwrap();
+        assert_eq!(alice.pending_rewards, 7_200);
+    }
+
+    #[test]
+    fn weight_change_mid_period_settles_at_the_old_weight() {
+        let schedule = EmissionSchedule::default();
+        let mut state = funded_pool();
+        let (mut alice, mut bob) = (UserStake::default(), UserStake::default());
+        alice.add_stake(&mut state, &schedule, 600, &clock(0, START)).unwrap();
+        bob.add_stake(&mut state, &schedule, 200, &clock(0, START)).unwrap();
+
+        // The top-up settles the first 96 slots at 720 before Alice's weight becomes 1200
+        alice.add_stake(&mut state, &schedule, 400, &clock(96, START)).unwrap();
+        assert_eq!(alice.pending_rewards, 7_200);
+        assert_eq!(alice.weighted_amount, 1_200);
+        assert_eq!(state.total_weighted_stake, 1_440);
+        assert_eq!(alice.reward_debt, calculate_reward_debt(1_200, state.acc_reward_per_share).unwrap());
+
+        state.update_rewards(&schedule, 240).unwrap();
+        alice.settle_rewards(&state).unwrap();
+        bob.settle_rewards(&state).unwrap();
+        assert_eq!((alice.pending_rewards, bob.pending_rewards), (7_200 + 12_000, 2_400 + 2_400));
+        assert_eq!(alice.pending_rewards + bob.pending_rewards, 240 * 100);
+        assert_eq!(state.total_rewards_owed, 240 * 100);
+    }
+
+    #[test]
+    fn emission_stops_at_unallocated_rewards_until_refunded() {
+        let schedule = EmissionSchedule::default();
+        let mut state = StakingState { total_funded: 5_000, ..funded_pool() };
+        let mut position = UserStake::default();
+        position.add_stake(&mut state, &schedule, 1_000, &clock(0, START)).unwrap();
+
+        // 100 slots schedule 10,000 but only 5,000 are funded
+        assert_eq!(state.pending_emission(&schedule, 100).unwrap(), 5_000);
+        state.update_rewards(&schedule, 100).unwrap();
+        assert_eq!(state.unallocated_rewards(), 0);
+        assert_eq!(state.reward_runway(&schedule, 100).unwrap(), 0);
+        state.update_rewards(&schedule, 200).unwrap();
+        assert_eq!(state.total_rewards_owed, 5_000);
+
+        // Underfunded slots are not paid retroactively
+        state.fund_rewards(1_000).unwrap();
+        assert_eq!(state.pending_emission(&schedule, 300).unwrap(), 1_000);
+        state.update_rewards(&schedule, 300).unwrap();
+        position.settle_rewards(&state).unwrap();
+        // Accumulator rounding never pays out more than was funded
+        assert!(position.pending_rewards <= 6_000 && position.pending_rewards >= 5_999);
+        assert_eq!(state.total_emitted, 6_000);
+
+        // Paying out frees nothing: it was already allocated
+        state.pay_out_rewards(position.pending_rewards).unwrap();
+        assert_eq!(state.pending_emission(&schedule, 400).unwrap(), 0);
+    }
+
+    #[test]
+    fn emission_stops_at_the_cap() {
+        let schedule = EmissionSchedule::new(EmissionCurve::Flat, &[], DecayCurve::default(), 3_000).unwrap();
+        let mut state = funded_pool();
+        let mut position = UserStake::default();
+        position.add_stake(&mut state, &schedule, 1_000, &clock(0, START)).unwrap();
+
+        assert_eq!(state.pending_emission(&schedule, 20).unwrap(), 2_000);
+        state.update_rewards(&schedule, 20).unwrap();
+        assert_eq!(state.remaining_emission_cap(&schedule), 1_000);
+        assert_eq!(state.reward_runway(&schedule, 20).unwrap(), 10);
+
+        state.update_rewards(&schedule, 100).unwrap();
+        assert_eq!(state.total_emitted, 3_000);
+        assert_eq!(state.remaining_emission_cap(&schedule), 0);
+        assert_eq!(state.pending_emission(&schedule, 1_000).unwrap(), 0);
+        // The funded balance is left over for a raised cap
+        assert_eq!(state.unallocated_rewards(), 1_000_000_000 - 3_000);
+    }
+
+    #[test]
+    fn nothing_is_emitted_without_stake() {
+        let schedule = EmissionSchedule::default();
+        let mut state = funded_pool();
+        assert_eq!(state.pending_emission(&schedule, 100).unwrap(), 0);
+        state.update_rewards(&schedule, 100).unwrap();
+        assert_eq!((state.total_emitted, state.last_update_slot), (0, 100));
+    }
+
+    #[test]
+    fn positions_of_one_owner_accrue_and_settle_independently() {
+        let schedule = EmissionSchedule::default();
+        let mut state = funded_pool();
+        let owner = Pubkey::new_unique();
+        let mut first = UserStake { owner, position_id: 0, ..Default::default() };
+        let mut second = UserStake { owner, position_id: 1, ..Default::default() };
+
+        first.add_stake(&mut state, &schedule, 800, &clock(0, START)).unwrap();
+        // The first position earns every slot on its own until the second opens
+        second.add_stake(&mut state, &schedule, 200, &clock(48, START)).unwrap();
+        assert_eq!(second.pending_rewards, 0);
+
+        state.update_rewards(&schedule, 144).unwrap();
+        first.settle_rewards(&state).unwrap();
+        second.settle_rewards(&state).unwrap();
+        assert_eq!((first.pending_rewards, second.pending_rewards), (4_800 + 7_680, 1_920));
+
+        // Closing out one position leaves the other's stake and rewards untouched
+        second.remove_stake(&mut state, &schedule, 200, &clock(144, START)).unwrap();
+        state.pay_out_rewards(second.pending_rewards).unwrap();
+        second.pending_rewards = 0;
+        assert_eq!((first.amount, first.weighted_amount, first.pending_rewards), (800, 960, 12_480));
+        assert_eq!(state.total_weighted_stake, 960);
+
+        // From then on the remaining position gets the whole emission
+        state.update_rewards(&schedule, 240).unwrap();
+        first.settle_rewards(&state).unwrap();
+        second.settle_rewards(&state).unwrap();
+        assert_eq!((first.pending_rewards, second.pending_rewards), (12_480 + 9_600, 0));
+    }
+
+    #[test]
+    fn refresh_level_follows_the_time_weighted_balance() {
+        let schedule = EmissionSchedule::default();
+        let mut state = funded_pool();
+        let mut position = UserStake::default();
+        position.add_stake(&mut state, &schedule, 10_000, &clock(0, START)).unwrap();
+        // Nothing has been held yet, so a Platinum-sized deposit starts at Bronze
+        assert_eq!(position.level, 0);
+
+        // Half a window in, the average is 5,000: Gold
+        position.refresh_level(&mut state, &clock(1, START + LEVEL_WINDOW / 2)).unwrap();
+        assert_eq!((position.twab_amount, position.level), (5_000, 2));
+        assert_eq!(position.weighted_amount, 20_000);
+        assert_eq!(state.total_weighted_stake, 20_000);
+
+        // Another half window only moves it to 7,500
+        position.refresh_level(&mut state, &clock(2, START + LEVEL_WINDOW)).unwrap();
+        assert_eq!((position.twab_amount, position.level), (7_500, 2));
+
+        // After a full window at 10,000 the average catches up: Platinum
+        position.refresh_level(&mut state, &clock(3, START + 2 * LEVEL_WINDOW)).unwrap();
+        assert_eq!((position.twab_amount, position.level), (10_000, 3));
+        assert_eq!(state.total_weighted_stake, 30_000);
+
+        // Unstaking lowers the level at once, whatever the average
+        position.remove_stake(&mut state, &schedule, 9_500, &clock(4, START + 2 * LEVEL_WINDOW)).unwrap();
+        assert_eq!((position.twab_amount, position.level), (10_000, 0));
+        assert_eq!(state.total_weighted_stake, 600);
+    }
+
+    #[test]
+    fn pool_overrides_survive_a_governance_sync() {
+        let mut governance = GovernanceState {
+            lock_tiers: DEFAULT_LOCK_TIERS,
+            lockup_period: LOCKUP_PERIOD,
+            claim_cooldown: CLAIM_COOLDOWN,
+            loyalty_bps_per_period: LOYALTY_BPS_PER_PERIOD,
+            loyalty_period: LOYALTY_PERIOD,
+            max_loyalty_bps: MAX_LOYALTY_BPS,
+            ..Default::default()
+        };
+        governance.level_tiers[..DEFAULT_LEVEL_TIER_COUNT as usize]
+            .copy_from_slice(&DEFAULT_LEVEL_TIERS[..DEFAULT_LEVEL_TIER_COUNT as usize]);
+        governance.level_tier_count = DEFAULT_LEVEL_TIER_COUNT;
+        let mut state = StakingState::default();
+        state.sync_from_governance(&governance, 0).unwrap();
+        assert!(state.lock_tier(&governance, 0).unwrap() == DEFAULT_LOCK_TIERS[0]);
+
+        let pool_level_tiers = [
+            LevelTier { min_amount: 0, multiplier: BASE_MULTIPLIER },
+            LevelTier { min_amount: 100, multiplier: 4000 },
+        ];
+        let pool_lock_tiers = [LockTier { duration: 60, multiplier: 5000 }; LOCK_TIER_COUNT];
+        state.set_level_tiers(&pool_level_tiers).unwrap();
+        state.custom_level_tiers = true;
+        state.set_lock_tiers(&pool_lock_tiers).unwrap();
+        state.lockup_period = 60;
+        state.custom_lockup_period = true;
+
+        governance.claim_cooldown = 2 * CLAIM_COOLDOWN;
+        state.sync_from_governance(&governance, 1).unwrap();
+        assert!(state.active_level_tiers() == pool_level_tiers);
+        assert!(state.lock_tier(&governance, 4).unwrap() == pool_lock_tiers[4]);
+        assert_eq!(state.lockup_period, 60);
+        // Settings the pool does not override still follow governance
+        assert_eq!(state.claim_cooldown, 2 * CLAIM_COOLDOWN);
+        assert!(state.lock_tier(&governance, LOCK_TIER_COUNT as u8).is_err());
+
+        // Lock tiers are bounded like governance's
+        let too_long = [LockTier { duration: MAX_LOCKUP_PERIOD + 1, multiplier: 5000 }; LOCK_TIER_COUNT];
+        assert!(state.set_lock_tiers(&too_long).is_err());
+        assert!(state.set_lock_tiers(&pool_lock_tiers[..1]).is_err());
+    }
+
+    #[test]
+    fn decay_period_must_not_be_too_short() {
+        let decay = DecayCurve {
+            start_slot: 0,
+            period_slots: MIN_DECAY_PERIOD_SLOTS - 1,
+            initial_rate_per_slot: 1_000_000,
+            decay_bps: 5000,
+            floor_rate_per_slot: 0,
+        };
+        assert!(EmissionSchedule::new(EmissionCurve::Decay, &[], decay, 0).is_err());
+    }
+
+    #[test]
+    fn only_empty_positions_idle_for_the_inactivity_period_are_abandoned() {
+        let now = 10 * SWEEP_INACTIVITY_PERIOD;
+        let mut position = UserStake { twab_updated_at: now - SWEEP_INACTIVITY_PERIOD + 1, ..Default::default() };
+        assert!(!position.is_abandoned(now));
+        position.twab_updated_at = now - SWEEP_INACTIVITY_PERIOD;
+        assert!(position.is_abandoned(now));
+        // A recent claim counts as activity
+        position.last_claimed_at = now - 1;
+        assert!(!position.is_abandoned(now));
+        position.last_claimed_at = 0;
+        position.pending_rewards = 1;
+        assert!(!position.is_abandoned(now));
+    }
+
+    #[test]
+    fn paying_out_owed_rewards_redeems_receipts_without_lowering_the_rate() {
+        let mint = Pubkey::new_unique();
+        let mut state = StakingState {
+            staking_mint: mint,
+            reward_mint: mint,
+            total_staked: 1_000,
+            total_rewards_owed: 500,
+            ..Default::default()
+        };
+        let mut position = UserStake { amount: 100, pending_rewards: 200, receipt_amount: 400, ..Default::default() };
+        let supply = 1_000;
+        let rate_before = calculate_exchange_rate(state.pooled_value().unwrap(), supply).unwrap();
+
+        let receipts = state.receipt_redemption(&position, position.pending_rewards, supply).unwrap();
+        assert_eq!(receipts, 134);
+        state.total_rewards_owed -= position.pending_rewards;
+        position.pending_rewards = 0;
+        assert_eq!(position.take_receipts(receipts), 134);
+        let rate_after = calculate_exchange_rate(state.pooled_value().unwrap(), supply - receipts).unwrap();
+        assert!(rate_after >= rate_before);
+
+        // The last exit takes whatever receipts are left
+        position.amount = 0;
+        assert_eq!(position.take_receipts(0), 266);
+        assert_eq!(position.receipt_amount, 0);
+
+        // Rewards in another token do not back receipts
+        state.reward_mint = Pubkey::new_unique();
+        assert_eq!(state.pooled_value().unwrap(), 1_000);
+        assert!(!state.rewards_back_receipts());
     }
 }
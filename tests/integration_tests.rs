@@ -4,7 +4,7 @@ use anchor_spl::token::{self, Mint, Token, TokenAccount};
 use anchor_client::solana_sdk::signature::Keypair;
 use anchor_client::solana_sdk::signer::Signer;
 use botanika_staking::state::{StakingState, UserStake};
-use botanika_staking::shared_types::{StakingLevel, StakingStatus};
+use botanika_staking::shared_types::StakingStatus;
 use botanika_rewards::state::{RewardPool, RewardRecipient, ProofType, RewardStatus};
 use botanika_governance::state::{GovernanceState, Proposal, ProposalType, ProposalStatus};
 use botanika_staking::ID as STAKING_PROGRAM_ID;
@@ -38,7 +38,7 @@ async fn test_cross_program_staking_and_rewards() {
     // Verify staking level
     let user_stake = get_user_stake_account(&mut context, &user).await;
     let user_stake_data: UserStake = get_account_data(&mut context, &user_stake).await;
-    assert_eq!(user_stake_data.level, StakingLevel::Gold);
+    assert_eq!(user_stake_data.level, 2); // Gold
 
     // Authority submits proof-based reward
     submit_proof_reward(&mut context, &authority, &reward_pool, &user.pubkey(), 1000, ProofType::ProofOfExecution).await;
@@ -72,14 +72,14 @@ async fn test_governance_multiplier_updates() {
 
     // Get initial multipliers
     let governance_data: GovernanceState = get_account_data(&mut context, &governance_state).await;
-    let initial_gold_multiplier = governance_data.multipliers[2]; // Gold level
+    let initial_gold_multiplier = governance_data.level_tiers[2].multiplier; // Gold level
 
     // Update multiplier through governance
     update_multiplier(&mut context, &authority, &governance_state, 2, 2500).await; // Increase Gold multiplier
 
     // Verify multiplier was updated
     let updated_governance_data: GovernanceState = get_account_data(&mut context, &governance_state).await;
-    assert_eq!(updated_governance_data.multipliers[2], 2500);
+    assert_eq!(updated_governance_data.level_tiers[2].multiplier, 2500);
 
     // User stakes and claims rewards with new multiplier
     mint_to_user(&mut context, &stake_mint, &user, &context.payer, 8000).await;
@@ -168,7 +168,7 @@ async fn test_comprehensive_user_journey() {
 
     // 7. Verify user reached Platinum level
     let updated_user_stake_data: UserStake = get_account_data(&mut context, &user_stake).await;
-    assert_eq!(updated_user_stake_data.level, StakingLevel::Platinum);
+    assert_eq!(updated_user_stake_data.level, 3); // Platinum
 
     // 8. More time passes
     advance_slot(&mut context, 300000).await;
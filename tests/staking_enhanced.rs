@@ -4,7 +4,7 @@ use anchor_spl::token::{self, Mint, Token, TokenAccount};
 use anchor_client::solana_sdk::signature::Keypair;
 use anchor_client::solana_sdk::signer::Signer;
 use botanika_staking::state::{StakingState, UserStake};
-use botanika_staking::shared_types::{StakingLevel, StakingStatus};
+use botanika_staking::shared_types::StakingStatus;
 use botanika_staking::ID as STAKING_PROGRAM_ID;
 use solana_program_test::*;
 use solana_sdk::{signature::read_keypair_file, transaction::Transaction};
@@ -23,48 +23,10 @@ async fn test_staking_initialization() {
     let staking_state_account: StakingState = get_account_data(&mut context, &staking_state).await;
     assert_eq!(staking_state_account.admin, authority.pubkey());
     assert_eq!(staking_state_account.total_staked, 0);
-    assert_eq!(staking_state_account.multipliers[0], 1200); // Bronze
-    assert_eq!(staking_state_account.multipliers[1], 1500); // Silver
-    assert_eq!(staking_state_account.multipliers[2], 2000); // Gold
-    assert_eq!(staking_state_account.multipliers[3], 3000); // Platinum
-}
-
-#[tokio::test]
-async fn test_staking_level_calculation() {
-    let mut context = setup_test_context().await;
-    let user = Keypair::new();
-    airdrop(&mut context, &user.pubkey(), 10_000_000_000).await;
-
-    let (staking_state, _, stake_mint, vault, _, _) = init_staking_system(&mut context, &user).await;
-
-    // Test Bronze level (0-1000)
-    mint_to_user(&mut context, &stake_mint, &user, &context.payer, 500).await;
-    stake_tokens(&mut context, &user, &staking_state, &stake_mint, 500).await;
-    
-    let user_stake = get_user_stake_account(&mut context, &user).await;
-    let user_stake_data: UserStake = get_account_data(&mut context, &user_stake).await;
-    assert_eq!(user_stake_data.level, StakingLevel::Bronze);
-
-    // Test Silver level (1001-5000)
-    mint_to_user(&mut context, &stake_mint, &user, &context.payer, 2000).await;
-    stake_tokens(&mut context, &user, &staking_state, &stake_mint, 2000).await;
-    
-    let user_stake_data: UserStake = get_account_data(&mut context, &user_stake).await;
-    assert_eq!(user_stake_data.level, StakingLevel::Silver);
-
-    // Test Gold level (5001-10000)
-    mint_to_user(&mut context, &stake_mint, &user, &context.payer, 3000).await;
-    stake_tokens(&mut context, &user, &staking_state, &stake_mint, 3000).await;
-    
-    let user_stake_data: UserStake = get_account_data(&mut context, &user_stake).await;
-    assert_eq!(user_stake_data.level, StakingLevel::Gold);
-
-    // Test Platinum level (10001+)
-    mint_to_user(&mut context, &stake_mint, &user, &context.payer, 5000).await;
-    stake_tokens(&mut context, &user, &staking_state, &stake_mint, 5000).await;
-    
-    let user_stake_data: UserStake = get_account_data(&mut context, &user_stake).await;
-    assert_eq!(user_stake_data.level, StakingLevel::Platinum);
+    assert_eq!(staking_state_account.level_tiers[0].multiplier, 1200); // Bronze
+    assert_eq!(staking_state_account.level_tiers[1].multiplier, 1500); // Silver
+    assert_eq!(staking_state_account.level_tiers[2].multiplier, 2000); // Gold
+    assert_eq!(staking_state_account.level_tiers[3].multiplier, 3000); // Platinum
 }
 
 #[tokio::test]
@@ -80,7 +42,7 @@ async fn test_lockup_period_validation() {
     stake_tokens(&mut context, &user, &staking_state, &stake_mint, 1000).await;
 
     // Try to unstake before lockup period (should fail)
-    let user_stake = get_user_stake_account(&mut context, &user).await;
+    let user_stake = get_user_stake_account(&mut context, &staking_state, &user).await;
     let result = unstake_tokens(&mut context, &user, &staking_state, &user_stake, &vault, &vault, &vault, &stake_mint).await;
     assert!(result.is_err());
 
@@ -111,7 +73,7 @@ async fn test_claim_cooldown_validation() {
     let reward_dest = create_token_account(&mut context, &reward_mint, &user).await;
 
     // First claim should work
-    let user_stake = get_user_stake_account(&mut context, &user).await;
+    let user_stake = get_user_stake_account(&mut context, &staking_state, &user).await;
     let result = claim_rewards(&mut context, &user, &user_stake, &reward_vault, &reward_dest).await;
     assert!(result.is_ok());
 
@@ -139,7 +101,7 @@ async fn test_multiplier_based_rewards() {
     mint_to_user(&mut context, &stake_mint, &user, &context.payer, 500).await;
     stake_tokens(&mut context, &user, &staking_state, &stake_mint, 500).await;
 
-    let user_stake = get_user_stake_account(&mut context, &user).await;
+    let user_stake = get_user_stake_account(&mut context, &staking_state, &user).await;
     let reward_dest = create_token_account(&mut context, &reward_mint, &user).await;
 
     // Advance time and claim
@@ -179,9 +141,14 @@ async fn test_overflow_protection() {
 }
 
 // Helper functions
-async fn get_user_stake_account(context: &mut ProgramTestContext, user: &Keypair) -> Pubkey {
+
+async fn get_user_stake_account(context: &mut ProgramTestContext, staking_state: &Pubkey, user: &Keypair) -> Pubkey {
+    get_position_account(staking_state, user, 0)
+}
+
+fn get_position_account(staking_state: &Pubkey, user: &Keypair, position_id: u64) -> Pubkey {
     let (user_stake, _) = Pubkey::find_program_address(
-        &[b"user-stake", user.pubkey().as_ref()],
+        &[b"user-stake", staking_state.as_ref(), user.pubkey().as_ref(), position_id.to_le_bytes().as_ref()],
         &STAKING_PROGRAM_ID,
     );
     user_stake